@@ -0,0 +1,162 @@
+//! Activities as rest resources under /api/activities. Unlike the
+//! older endpoints every error is a json body like
+//! `{"error": "not_found", "message": "not found: activity 7"}`.
+
+use crate::structs::dates::parse_at;
+use crate::structs::error::TrackerError;
+use crate::structs::task::{sys_now_secs, ActivityChanges, Interval};
+use crate::TaskManager;
+use actix_web::error::InternalError;
+use actix_web::web::{Data, Json, JsonConfig, Path, PathConfig};
+use actix_web::{delete, get, patch, post, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: &'static str,
+    message: String,
+}
+
+pub fn json_error(error: TrackerError) -> HttpResponse {
+    let mut response = match error {
+        TrackerError::NotFound(_) => HttpResponse::NotFound(),
+        TrackerError::Conflict(_) => HttpResponse::Conflict(),
+        TrackerError::Storage(_) => HttpResponse::InternalServerError(),
+        _ => HttpResponse::BadRequest(),
+    };
+    response.json(ErrorBody {
+        error: error.code(),
+        message: error.to_string(),
+    })
+}
+
+/// bodies that can't be read get a json error too.
+pub fn json_config() -> JsonConfig {
+    JsonConfig::default().error_handler(|error, _| {
+        let response = json_error(TrackerError::InvalidValue(error.to_string()));
+        InternalError::from_response(error, response).into()
+    })
+}
+
+/// ids that aren't numbers get a json error too.
+pub fn path_config() -> PathConfig {
+    PathConfig::default().error_handler(|error, _| {
+        let response = json_error(TrackerError::InvalidValue(error.to_string()));
+        InternalError::from_response(error, response).into()
+    })
+}
+
+fn json_result<T: Serialize>(result: Result<T, TrackerError>) -> HttpResponse {
+    match result {
+        Ok(value) => HttpResponse::Ok().json(value),
+        Err(error) => json_error(error),
+    }
+}
+
+#[get("")]
+pub async fn list_activities(req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    HttpResponse::Ok().json(tm.activities())
+}
+
+/// A stopped activity from a body like {"name": "review", "project":
+/// "billing", "tags": ["backend"], "notes": "", "concurrent": false}.
+#[post("")]
+pub async fn create_activity(body: Json<ActivityChanges>, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let mut tm = data.lock().unwrap();
+    match tm.create_activity(&body) {
+        Ok(task) => HttpResponse::Created()
+            .insert_header(("Location", format!("/api/activities/{}", task.id())))
+            .json(task),
+        Err(error) => json_error(error),
+    }
+}
+
+#[get("/{id}")]
+pub async fn get_activity(id: Path<u64>, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    json_result(tm.activity(*id))
+}
+
+/// Sets the fields in the body, the others stay as they are.
+#[patch("/{id}")]
+pub async fn update_activity(
+    id: Path<u64>,
+    body: Json<ActivityChanges>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let mut tm = data.lock().unwrap();
+    json_result(tm.update_activity(*id, &body))
+}
+
+#[delete("/{id}")]
+pub async fn delete_activity(id: Path<u64>, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let mut tm = data.lock().unwrap();
+    match tm.delete_activity(*id) {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(error) => json_error(error),
+    }
+}
+
+/// The recorded intervals, oldest first. A running activity's last
+/// interval ends now.
+#[get("/{id}/intervals")]
+pub async fn list_intervals(id: Path<u64>, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    json_result(tm.activity(*id).map(|task| task.intervals()))
+}
+
+/// Times take the formats of the ?at= of start.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IntervalBody {
+    start: String,
+    end: String,
+}
+
+#[post("/{id}/intervals")]
+pub async fn create_interval(
+    id: Path<u64>,
+    body: Json<IntervalBody>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let now = sys_now_secs();
+    let added = parse_at(&body.start, now).and_then(|start| {
+        let interval = Interval {
+            start,
+            end: parse_at(&body.end, now)?,
+        };
+        let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+        let mut tm = data.lock().unwrap();
+        let task = tm.activity(*id)?;
+        tm.add_interval(&task.name(), interval.start, interval.end)?;
+        Ok(interval)
+    });
+    match added {
+        Ok(interval) => HttpResponse::Created().json(interval),
+        Err(error) => json_error(error),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    #[actix_web::test]
+    async fn errors_are_json() {
+        let response = json_error(TrackerError::Conflict("review already exists".to_string()));
+        assert_eq!(response.status(), 409);
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "conflict");
+        assert_eq!(body["message"], "conflict: review already exists");
+    }
+}
@@ -1,35 +1,809 @@
+use super::resources::json_error;
+use crate::formats;
+use crate::formats::csv::{self, CsvOptions};
+use crate::formats::ical::{self, IcalOptions};
+use crate::formats::org;
+use crate::formats::prometheus;
+use crate::formats::standup::{self, StandupOptions, Style};
+use crate::formats::timeclock;
+use crate::formats::timewarrior;
+use crate::structs::analytics::Focus;
+use crate::structs::backup::Backup;
+use crate::structs::compare::{Baseline, Comparison};
+use crate::structs::dates::{check_range, local_date, parse_at, parse_date, resolve_time};
+use crate::structs::error::TrackerError;
+use crate::structs::filter::{Sort, TaskFilter};
+use crate::structs::metrics::HttpMetrics;
+use crate::structs::pomodoro::{phase_secs, work_secs};
+use crate::structs::report::Period;
+use crate::structs::schedule::Action;
+use crate::structs::stats::{Breakdown, DailyStats, Group};
+use crate::structs::task::{sys_now_secs, Activity, SecType};
+use crate::structs::timeline::Timeline;
 use crate::TaskManager;
-use actix_web::web::{Data, Json, Path};
-use actix_web::{get, post, HttpRequest, HttpResponse, Responder, Result};
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, post, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
 use std::sync::Mutex;
 
+#[derive(Deserialize)]
+pub struct StartQuery {
+    /// pomodoro work phase in minutes.
+    work: Option<u64>,
+    /// pomodoro break phase in minutes.
+    #[serde(rename = "break")]
+    break_mins: Option<u64>,
+    /// let the activity run next to the others.
+    concurrent: Option<bool>,
+    /// puts the activity into a project, empty removes it.
+    project: Option<String>,
+    /// comma separated tags, replaces the earlier tags.
+    tags: Option<String>,
+    /// free text notes, empty removes them.
+    notes: Option<String>,
+    /// backdated start time.
+    at: Option<String>,
+    /// backdated start as a duration before now, like 15m.
+    ago: Option<String>,
+}
+
+/// Stopping can be backdated the same way as starting.
+#[derive(Deserialize)]
+pub struct StopQuery {
+    at: Option<String>,
+    ago: Option<String>,
+}
+
 #[post("start/{name}")]
-pub async fn start(name: Path<String>, req: HttpRequest) -> HttpResponse {
+pub async fn start(name: Path<String>, query: Query<StartQuery>, req: HttpRequest) -> HttpResponse {
+    let query = query.into_inner();
+    let options = StartBody {
+        name: name.into_inner(),
+        work: query.work,
+        break_mins: query.break_mins,
+        concurrent: query.concurrent,
+        project: query.project,
+        tags: query
+            .tags
+            .map(|tags| tags.split(',').map(|tag| tag.to_string()).collect()),
+        notes: query.notes,
+        at: query.at,
+        ago: query.ago,
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    match start_activity(data, &options) {
+        Ok(task) => HttpResponse::Ok().body(format!("activated task: {} Ok.", task.name())),
+        Err(error) => error_response(error),
+    }
+}
+
+/// The options of start as a json body, for names that don't fit
+/// into a url, like {"name": "review #12", "tags": ["backend"]}.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StartBody {
+    name: String,
+    work: Option<u64>,
+    #[serde(rename = "break")]
+    break_mins: Option<u64>,
+    concurrent: Option<bool>,
+    project: Option<String>,
+    tags: Option<Vec<String>>,
+    notes: Option<String>,
+    at: Option<String>,
+    ago: Option<String>,
+}
+
+/// Starts the activity in the body and answers with it. Errors are
+/// json like the ones of /api/activities.
+#[post("start")]
+pub async fn start_json(body: String, req: HttpRequest) -> HttpResponse {
+    let options: StartBody = match serde_json::from_str(&body) {
+        Ok(options) => options,
+        Err(error) => return json_error(TrackerError::InvalidValue(error.to_string())),
+    };
     let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    match start_activity(data, &options) {
+        Ok(task) => HttpResponse::Ok().json(task),
+        Err(error) => json_error(error),
+    }
+}
+
+/// starts an activity with its options and returns it. The input is
+/// checked before the task manager is locked and a backdated start
+/// before the options are saved, so a refused start changes nothing.
+fn start_activity(
+    data: &Mutex<TaskManager>,
+    options: &StartBody,
+) -> Result<Activity, TrackerError> {
+    let t = backdated(&options.at, &options.ago)?;
+    let phases = match options.work {
+        Some(work) => Some((
+            work_secs(work)?,
+            phase_secs(options.break_mins.unwrap_or(0))?,
+        )),
+        None => None,
+    };
     let mut tm = data.lock().unwrap();
-    tm.start(&name);
-    HttpResponse::Ok().body(format!("activated task: {} Ok.", name))
+    let name = tm.resolve_name(&options.name)?;
+    if let Some(t) = t {
+        tm.check_start(t)?;
+    }
+    if let Some(concurrent) = options.concurrent {
+        tm.set_concurrent(&name, concurrent);
+    }
+    if let Some(project) = &options.project {
+        tm.set_project(&name, project);
+    }
+    if let Some(tags) = &options.tags {
+        tm.set_tags(&name, tags);
+    }
+    if let Some(notes) = &options.notes {
+        tm.set_notes(&name, notes);
+    }
+    match (phases, t) {
+        (Some((work_secs, break_secs)), t) => {
+            tm.start_pomodoro(&name, work_secs, break_secs, t.unwrap_or_else(sys_now_secs))?
+        }
+        (None, Some(t)) => tm.start_at(&name, t)?,
+        (None, None) => tm.start(&name),
+    }
+    Ok(tm
+        .activities()
+        .into_iter()
+        .find(|task| task.name() == name)
+        .unwrap())
 }
 
 #[post("stop")]
-pub async fn stop(req: HttpRequest) -> impl Responder {
+pub async fn stop(query: Query<StopQuery>, req: HttpRequest) -> HttpResponse {
+    let stopped = backdated(&query.at, &query.ago).and_then(|t| {
+        let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+        let mut tm = data.lock().unwrap();
+        match t {
+            Some(t) => tm.stop_at(t),
+            None => {
+                tm.stop();
+                Ok(())
+            }
+        }
+    });
+    match stopped {
+        Ok(()) => HttpResponse::Ok().body("ok"),
+        Err(error) => error_response(error),
+    }
+}
+
+#[post("stop/{name}")]
+pub async fn stop_one(
+    name: Path<String>,
+    query: Query<StopQuery>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let stopped = backdated(&query.at, &query.ago).and_then(|t| {
+        let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+        let mut tm = data.lock().unwrap();
+        let name = tm.resolve_name(&name)?;
+        tm.stop_one(&name, t.unwrap_or_else(sys_now_secs))?;
+        Ok(name)
+    });
+    match stopped {
+        Ok(name) => HttpResponse::Ok().body(format!("stopped task: {} Ok.", name)),
+        Err(error) => error_response(error),
+    }
+}
+
+/// the requested time of a start or stop, None when it happens now.
+fn backdated(at: &Option<String>, ago: &Option<String>) -> Result<Option<SecType>, TrackerError> {
+    if at.is_none() && ago.is_none() {
+        return Ok(None);
+    }
+    resolve_time(at.as_deref(), ago.as_deref(), sys_now_secs()).map(Some)
+}
+
+fn error_response(error: TrackerError) -> HttpResponse {
+    match error {
+        TrackerError::NotFound(_) => HttpResponse::NotFound().body(error.to_string()),
+        TrackerError::Conflict(_) => HttpResponse::Conflict().body(error.to_string()),
+        TrackerError::Storage(_) => HttpResponse::InternalServerError().body(error.to_string()),
+        _ => HttpResponse::BadRequest().body(error.to_string()),
+    }
+}
+
+#[post("resume")]
+pub async fn resume(req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let mut tm = data.lock().unwrap();
+    match tm.resume() {
+        Some(name) => HttpResponse::Ok().body(format!("resumed task: {} Ok.", name)),
+        None => HttpResponse::NotFound().body("no previous task to resume."),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    limit: Option<usize>,
+}
+
+#[get("history")]
+pub async fn history(query: Query<HistoryQuery>, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    HttpResponse::Ok().json(tm.history(query.limit.unwrap_or(20)))
+}
+
+#[get("schedules")]
+pub async fn schedules(req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    match tm.schedules() {
+        Ok(schedules) => HttpResponse::Ok().json(schedules),
+        Err(error) => error_response(error),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NewSchedule {
+    /// local time of day as HH:MM.
+    at: String,
+    #[serde(flatten)]
+    action: Action,
+    #[serde(default)]
+    daily: bool,
+}
+
+#[post("schedules")]
+pub async fn add_schedule(body: Json<NewSchedule>, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let mut tm = data.lock().unwrap();
+    let body = body.into_inner();
+    match tm.add_schedule(&body.at, body.action, body.daily) {
+        Ok(schedule) => HttpResponse::Created().json(schedule),
+        Err(error) => error_response(error),
+    }
+}
+
+#[delete("schedules/{id}")]
+pub async fn remove_schedule(id: Path<u64>, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let mut tm = data.lock().unwrap();
+    match tm.remove_schedule(*id) {
+        Ok(()) => HttpResponse::Ok().body(format!("removed schedule: {} Ok.", id)),
+        Err(error) => error_response(error),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ReportQuery {
+    /// any date in the period, today by default.
+    date: Option<String>,
+    /// explicit first date, overrides the period.
+    from: Option<String>,
+    /// explicit last date, overrides the period.
+    to: Option<String>,
+    /// "text" for a text table, json otherwise.
+    format: Option<String>,
+}
+
+#[get("reports/{period}")]
+pub async fn report(
+    period: Path<String>,
+    query: Query<ReportQuery>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let period = match period.parse::<Period>() {
+        Ok(period) => period,
+        Err(error) => return error_response(TrackerError::InvalidValue(error)),
+    };
+    let range = report_range(period, &query);
+    let (from, to) = match range {
+        Ok(range) => range,
+        Err(error) => return error_response(error),
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    let period_report = match tm.report(from, to) {
+        Ok(period_report) => period_report,
+        Err(error) => return error_response(error),
+    };
+    match query.format.as_deref() {
+        Some("text") => HttpResponse::Ok().body(period_report.text()),
+        _ => HttpResponse::Ok().json(period_report),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CompareQuery {
+    date: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+    /// previous or last_year, previous by default.
+    with: Option<String>,
+}
+
+#[get("reports/{period}/compare")]
+pub async fn compare_report(
+    period: Path<String>,
+    query: Query<CompareQuery>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let period = match period.parse::<Period>() {
+        Ok(period) => period,
+        Err(error) => return error_response(TrackerError::InvalidValue(error)),
+    };
+    let baseline = match query
+        .with
+        .as_deref()
+        .unwrap_or("previous")
+        .parse::<Baseline>()
+    {
+        Ok(baseline) => baseline,
+        Err(error) => return error_response(TrackerError::InvalidValue(error)),
+    };
+    let range_query = ReportQuery {
+        date: query.date.to_owned(),
+        from: query.from.to_owned(),
+        to: query.to.to_owned(),
+        format: None,
+    };
+    let (from, to) = match report_range(period, &range_query) {
+        Ok(range) => range,
+        Err(error) => return error_response(error),
+    };
+    let (previous_from, previous_to) = baseline.range(period, from, to);
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    let reports = tm
+        .report(from, to)
+        .and_then(|current| Ok((current, tm.report(previous_from, previous_to)?)));
+    let comparison = match reports {
+        Ok((current, previous)) => Comparison::new(&current, &previous),
+        Err(error) => return error_response(error),
+    };
+    match query.format.as_deref() {
+        Some("text") => HttpResponse::Ok().body(comparison.text()),
+        _ => HttpResponse::Ok().json(comparison),
+    }
+}
+
+fn report_range(
+    period: Period,
+    query: &ReportQuery,
+) -> Result<(chrono::NaiveDate, chrono::NaiveDate), TrackerError> {
+    let date = match &query.date {
+        Some(date) => parse_date(date)?,
+        None => local_date(sys_now_secs()),
+    };
+    let (mut from, mut to) = period.range(date);
+    if let Some(text) = &query.from {
+        from = parse_date(text)?;
+    }
+    if let Some(text) = &query.to {
+        to = parse_date(text)?;
+    }
+    check_range(from, to)?;
+    Ok((from, to))
+}
+
+/// days shown by the stats endpoints without a range.
+const STATS_DAYS: i64 = 14;
+
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    /// first date, STATS_DAYS before `to` by default.
+    from: Option<String>,
+    /// last date, today by default.
+    to: Option<String>,
+    /// activity or project, for the totals.
+    group: Option<String>,
+}
+
+impl StatsQuery {
+    fn range(&self) -> Result<(chrono::NaiveDate, chrono::NaiveDate), TrackerError> {
+        let to = match &self.to {
+            Some(to) => parse_date(to)?,
+            None => local_date(sys_now_secs()),
+        };
+        let from = match &self.from {
+            Some(from) => parse_date(from)?,
+            None => to - chrono::Duration::days(STATS_DAYS - 1),
+        };
+        check_range(from, to)?;
+        Ok((from, to))
+    }
+}
+
+#[get("stats/daily")]
+pub async fn daily_stats(query: Query<StatsQuery>, req: HttpRequest) -> HttpResponse {
+    let (from, to) = match query.range() {
+        Ok(range) => range,
+        Err(error) => return error_response(error),
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    match tm.report(from, to) {
+        Ok(days) => HttpResponse::Ok().json(DailyStats::new(&days)),
+        Err(error) => error_response(error),
+    }
+}
+
+#[get("stats/totals")]
+pub async fn total_stats(query: Query<StatsQuery>, req: HttpRequest) -> HttpResponse {
+    let (from, to) = match query.range() {
+        Ok(range) => range,
+        Err(error) => return error_response(error),
+    };
+    let group = match query
+        .group
+        .as_deref()
+        .unwrap_or("activity")
+        .parse::<Group>()
+    {
+        Ok(group) => group,
+        Err(error) => return error_response(TrackerError::InvalidValue(error)),
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    match tm.report(from, to) {
+        Ok(totals) => HttpResponse::Ok().json(Breakdown::new(&totals, group)),
+        Err(error) => error_response(error),
+    }
+}
+
+/// takes the same range as the stats.
+#[get("analytics/focus")]
+pub async fn focus(query: Query<StatsQuery>, req: HttpRequest) -> HttpResponse {
+    let (from, to) = match query.range() {
+        Ok(range) => range,
+        Err(error) => return error_response(error),
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    match Focus::new(&tm.activities(), from, to) {
+        Ok(focus) => HttpResponse::Ok().json(focus),
+        Err(error) => error_response(error),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TimelineQuery {
+    /// YYYY-MM-DD, today by default.
+    date: Option<String>,
+    /// shortest gap listed in minutes, 1 by default.
+    min_gap: Option<u64>,
+}
+
+#[get("timeline")]
+pub async fn timeline(query: Query<TimelineQuery>, req: HttpRequest) -> HttpResponse {
+    let date = match &query.date {
+        Some(date) => match parse_date(date) {
+            Ok(date) => date,
+            Err(error) => return error_response(error),
+        },
+        None => local_date(sys_now_secs()),
+    };
+    let min_gap = match query.min_gap.unwrap_or(1).checked_mul(60) {
+        Some(min_gap) => min_gap,
+        None => {
+            return error_response(TrackerError::InvalidValue(
+                "min_gap is too long".to_string(),
+            ))
+        }
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    match Timeline::new(&tm.activities(), date, min_gap) {
+        Ok(timeline) => HttpResponse::Ok().json(timeline),
+        Err(error) => error_response(error),
+    }
+}
+
+/// Time spent on an activity that was not tracked.
+/// The times take the same formats as the ?at= of start.
+#[derive(Deserialize)]
+pub struct NewInterval {
+    name: String,
+    start: String,
+    end: String,
+}
+
+#[post("intervals")]
+pub async fn add_interval(body: Json<NewInterval>, req: HttpRequest) -> HttpResponse {
+    let now = sys_now_secs();
+    let added = parse_at(&body.start, now).and_then(|from| {
+        let to = parse_at(&body.end, now)?;
+        let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+        let mut tm = data.lock().unwrap();
+        let name = tm.resolve_name(&body.name)?;
+        tm.add_interval(&name, from, to)?;
+        Ok(name)
+    });
+    match added {
+        Ok(name) => HttpResponse::Created().body(format!("added interval to: {} Ok.", name)),
+        Err(error) => error_response(error),
+    }
+}
+
+/// Timewarrior data lines of every month in one text.
+#[get("export.data")]
+pub async fn export_timewarrior(req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(
+            timewarrior::export(&tm.activities())
+                .into_values()
+                .collect::<String>(),
+        )
+}
+
+/// clock in and out lines for ledger and hledger.
+#[get("export.timeclock")]
+pub async fn export_timeclock(req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(timeclock::export(&tm.activities()))
+}
+
+/// an org file with CLOCK lines for Emacs.
+#[get("export.org")]
+pub async fn export_org(req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(org::export(&tm.activities()))
+}
+
+/// the whole store as a versioned json archive.
+#[get("backup")]
+pub async fn backup(req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    let archive = match tm.backup() {
+        Ok(archive) => archive,
+        Err(error) => return error_response(error),
+    };
+    HttpResponse::Ok()
+        .insert_header((
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"timetracker-{}.json\"",
+                local_date(sys_now_secs()).format("%Y-%m-%d")
+            ),
+        ))
+        .json(archive)
+}
+
+/// Replaces the store with an archive from backup.
+#[post("restore")]
+pub async fn restore(body: String, req: HttpRequest) -> HttpResponse {
+    let archive: Backup = match serde_json::from_str(&body) {
+        Ok(archive) => archive,
+        Err(error) => {
+            return error_response(TrackerError::InvalidValue(format!(
+                "not a backup: {}",
+                error
+            )))
+        }
+    };
     let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
     let mut tm = data.lock().unwrap();
-    tm.stop();
-    "ok"
+    let (activity_count, schedule_count) = (archive.activities.len(), archive.schedules.len());
+    match tm.restore(archive) {
+        Ok(backup_path) => HttpResponse::Ok().body(format!(
+            "restored {} activities and {} schedules, the previous state is in {}.",
+            activity_count,
+            schedule_count,
+            backup_path.display()
+        )),
+        Err(error) => error_response(error),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    /// auto, toggl, clockify, timewarrior, timeclock or org.
+    format: Option<String>,
+    /// only report what would be added.
+    dry_run: Option<bool>,
+}
+
+/// Takes a Toggl or Clockify csv export, Timewarrior data, a
+/// timeclock file or an org file as the body.
+#[post("import")]
+pub async fn import(query: Query<ImportQuery>, body: String, req: HttpRequest) -> HttpResponse {
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let known: Vec<String> = data
+        .lock()
+        .unwrap()
+        .activities()
+        .iter()
+        .map(|t| t.name())
+        .collect();
+    let file = formats::import_entries(&body, query.format.as_deref(), &known);
+    match file {
+        Ok(file) => {
+            let mut tm = data.lock().unwrap();
+            HttpResponse::Ok().json(tm.import(&file, query.dry_run.unwrap_or(false)))
+        }
+        Err(error) => error_response(error),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CsvQuery {
+    rows: Option<String>,
+    columns: Option<String>,
+    delimiter: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[get("export.csv")]
+pub async fn export_csv(query: Query<CsvQuery>, req: HttpRequest) -> HttpResponse {
+    let options = CsvOptions::parse(
+        query.rows.as_deref(),
+        query.columns.as_deref(),
+        query.delimiter.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+    );
+    let options = match options {
+        Ok(options) => options,
+        Err(error) => return error_response(error),
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .body(csv::export(&tm.activities(), &options, &tm.rounding()))
+}
+
+#[derive(Deserialize)]
+pub struct IcalQuery {
+    project: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[get("export.ics")]
+pub async fn export_ics(query: Query<IcalQuery>, req: HttpRequest) -> HttpResponse {
+    let options = IcalOptions::parse(
+        query.project.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+    );
+    let options = match options {
+        Ok(options) => options,
+        Err(error) => return error_response(error),
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(ical::export(&tm.activities(), &options, sys_now_secs()))
+}
+
+#[derive(Deserialize)]
+pub struct StandupQuery {
+    range: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+}
+
+#[get("standup")]
+pub async fn standup_report(query: Query<StandupQuery>, req: HttpRequest) -> HttpResponse {
+    let options = StandupOptions::parse(
+        query.range.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+        query.format.as_deref(),
+        local_date(sys_now_secs()),
+    );
+    let options = match options {
+        Ok(options) => options,
+        Err(error) => return error_response(error),
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    let summary = match tm.report(options.from, options.to) {
+        Ok(summary) => summary,
+        Err(error) => return error_response(error),
+    };
+    let content_type = match options.style {
+        Style::Markdown => "text/markdown; charset=utf-8",
+        Style::Text => "text/plain; charset=utf-8",
+    };
+    HttpResponse::Ok()
+        .content_type(content_type)
+        .body(standup::render(&summary, &tm.activities(), options.style))
+}
+
+/// registered outside of /api where prometheus looks by default.
+#[get("/metrics")]
+pub async fn metrics(req: HttpRequest) -> HttpResponse {
+    let http = req.app_data::<Data<Mutex<HttpMetrics>>>().unwrap();
+    let http = http.lock().unwrap().clone();
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    let today = local_date(sys_now_secs());
+    let today_report = match tm.report(today, today) {
+        Ok(today_report) => today_report,
+        Err(error) => return error_response(error),
+    };
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(prometheus::render(&tm.activities(), &today_report, &http))
+}
+
+/// Narrows the task list of times and pretty, the totals stay the same.
+#[derive(Deserialize)]
+pub struct TimesQuery {
+    /// part of the name, case insensitive.
+    name: Option<String>,
+    regex: Option<String>,
+    tag: Option<String>,
+    project: Option<String>,
+    active: Option<bool>,
+    /// only tasks with time tracked in the range, YYYY-MM-DD.
+    from: Option<String>,
+    to: Option<String>,
+    /// added, total, last_used or name.
+    sort: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl TimesQuery {
+    fn filter(&self) -> Result<TaskFilter, TrackerError> {
+        let mut filter = TaskFilter {
+            name: self.name.to_owned(),
+            tag: self.tag.to_owned(),
+            project: self.project.to_owned(),
+            active_only: self.active.unwrap_or(false),
+            offset: self.offset.unwrap_or(0),
+            limit: self.limit,
+            ..TaskFilter::default()
+        };
+        if let Some(regex) = &self.regex {
+            filter.set_regex(regex)?;
+        }
+        filter.set_range(self.from.as_deref(), self.to.as_deref())?;
+        if let Some(sort) = &self.sort {
+            filter.sort = sort.parse::<Sort>()?;
+        }
+        Ok(filter)
+    }
 }
 
 #[get("times")]
-pub async fn times(req: HttpRequest) -> Result<impl Responder> {
+pub async fn times(query: Query<TimesQuery>, req: HttpRequest) -> HttpResponse {
+    let filter = match query.filter() {
+        Ok(filter) => filter,
+        Err(error) => return error_response(error),
+    };
     let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
-    let tm = data.lock().unwrap().clone();
-    Ok(Json(tm))
+    let mut tm = data.lock().unwrap();
+    tm.tick();
+    HttpResponse::Ok().json(tm.listing(&filter))
 }
 
 #[get("pretty")]
-pub async fn pretty(req: HttpRequest) -> String {
+pub async fn pretty(query: Query<TimesQuery>, req: HttpRequest) -> HttpResponse {
+    let filter = match query.filter() {
+        Ok(filter) => filter,
+        Err(error) => return error_response(error),
+    };
     let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
-    let tm = data.lock().unwrap();
-    println!("{}", &tm.times());
-    tm.times()
+    let mut tm = data.lock().unwrap();
+    tm.tick();
+    let text = tm.times(&filter);
+    println!("{}", &text);
+    HttpResponse::Ok().body(text)
 }
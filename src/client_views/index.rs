@@ -3,16 +3,19 @@ use actix_web::{get, HttpRequest, HttpResponse};
 
 #[get("/")]
 async fn index_view(req: HttpRequest) -> HttpResponse {
+    static_page(&req, "index.html")
+}
+
+/// a page from the static dir next to the executable.
+pub fn static_page(req: &HttpRequest, name: &str) -> HttpResponse {
     let exe_path = std::env::current_exe().unwrap();
     let current_dir = exe_path.parent().unwrap();
-    let index_path = format!("{}/{}", current_dir.display(), "static/index.html");
-    let index_path_ref = index_path.clone();
-    match NamedFile::open(index_path) {
-        Ok(named_file) => named_file.into_response(&req),
+    let path = format!("{}/static/{}", current_dir.display(), name);
+    match NamedFile::open(&path) {
+        Ok(named_file) => named_file.into_response(req),
         Err(error) => HttpResponse::Ok().body(format!(
-            "Error with index.html: {}. Expected path: {}.",
-            error,
-            index_path_ref
+            "Error with {}: {}. Expected path: {}.",
+            name, error, path
         )),
     }
 }
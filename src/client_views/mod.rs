@@ -1 +1,3 @@
 pub mod index;
+pub mod pages;
+pub mod timesheet;
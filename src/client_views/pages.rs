@@ -0,0 +1,15 @@
+use actix_web::{get, HttpRequest, HttpResponse};
+
+use super::index::static_page;
+
+/// charts of the tracked time, built from /api/stats.
+#[get("/history")]
+pub async fn history_view(req: HttpRequest) -> HttpResponse {
+    static_page(&req, "history.html")
+}
+
+/// a day as coloured blocks, gaps can be filled in by clicking them.
+#[get("/timeline")]
+pub async fn timeline_view(req: HttpRequest) -> HttpResponse {
+    static_page(&req, "timeline.html")
+}
@@ -0,0 +1,187 @@
+use crate::structs::dates::{local_date, parse_week};
+use crate::structs::report::{signed_secs_to_pretty, DayTotal, Period, Report};
+use crate::structs::task::{secs_to_pretty, sys_now_secs};
+use crate::TaskManager;
+use actix_web::web::{Data, Query};
+use actix_web::{get, HttpRequest, HttpResponse};
+use chrono::Duration;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+const STYLE: &str = "
+    body { font-family: sans-serif; font-size: 11pt; margin: 2em; }
+    h1 { font-size: 16pt; margin-bottom: 0; }
+    p.period { margin-top: 0.2em; color: #444; }
+    table { border-collapse: collapse; width: 100%; }
+    th, td { border: 1px solid #888; padding: 0.3em 0.5em; }
+    td { text-align: right; }
+    th.name, td.name { text-align: left; }
+    tfoot td, tfoot th { font-weight: bold; background: #eee; }
+    .signatures { display: flex; gap: 4em; margin-top: 4em; }
+    .signature { flex: 1; border-top: 1px solid #000; padding-top: 0.3em; }
+    @media print {
+        body { margin: 0; }
+        tfoot td, tfoot th { -webkit-print-color-adjust: exact; print-color-adjust: exact; }
+        @page { size: A4 landscape; margin: 1.5cm; }
+    }
+";
+
+#[derive(Deserialize)]
+pub struct TimesheetQuery {
+    /// ISO week like 2026-W42 or any date in the week, this week by default.
+    week: Option<String>,
+    /// printed in the header above the signature.
+    employee: Option<String>,
+}
+
+#[get("/reports/timesheet")]
+pub async fn timesheet_view(query: Query<TimesheetQuery>, req: HttpRequest) -> HttpResponse {
+    let monday = match &query.week {
+        Some(week) => match parse_week(week) {
+            Ok(monday) => monday,
+            Err(error) => return HttpResponse::BadRequest().body(error.to_string()),
+        },
+        None => Period::Week.range(local_date(sys_now_secs())).0,
+    };
+    let data = req.app_data::<Data<Mutex<TaskManager>>>().unwrap();
+    let tm = data.lock().unwrap();
+    let report = match tm.report(monday, monday + Duration::days(6)) {
+        Ok(report) => report,
+        Err(error) => return HttpResponse::BadRequest().body(error.to_string()),
+    };
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(render(&report, query.employee.as_deref()))
+}
+
+/// A page with one row per activity and one column per day.
+pub fn render(report: &Report, employee: Option<&str>) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"UTF-8\">\n");
+    html.push_str(&format!(
+        "<title>Timesheet {} - {}</title>\n",
+        report.from, report.to
+    ));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str("<h1>Timesheet</h1>\n");
+    html.push_str(&format!(
+        "<p class=\"period\">{} - {}",
+        report.from, report.to
+    ));
+    if let Some(employee) = employee {
+        html.push_str(&format!(" &middot; {}", escape(employee)));
+    }
+    html.push_str("</p>\n<table>\n<thead>\n<tr><th class=\"name\">activity</th>");
+    for day in report.days.iter() {
+        html.push_str(&format!("<th>{}<br>{}</th>", day.weekday, day.date));
+    }
+    html.push_str("<th>total</th></tr>\n</thead>\n<tbody>\n");
+    for total in report.activities.iter() {
+        html.push_str(&format!(
+            "<tr><td class=\"name\">{}</td>",
+            escape(&total.name)
+        ));
+        for day in report.days.iter() {
+            let cell = match day.activities.get(&total.name) {
+                Some(secs) => secs_to_pretty(*secs),
+                None => String::new(),
+            };
+            html.push_str(&format!("<td>{}</td>", cell));
+        }
+        html.push_str(&format!("<td>{}</td></tr>\n", total.pretty));
+    }
+    html.push_str("</tbody>\n<tfoot>\n");
+    footer_row(
+        &mut html,
+        "daily total",
+        report,
+        |day| secs_to_pretty(day.total_secs),
+        &report.total_pretty,
+    );
+    if report.wall_clock_secs != report.total_secs {
+        footer_row(
+            &mut html,
+            "worked",
+            report,
+            |day| secs_to_pretty(day.wall_clock_secs),
+            &secs_to_pretty(report.wall_clock_secs),
+        );
+    }
+    footer_row(
+        &mut html,
+        "target",
+        report,
+        |day| secs_to_pretty(day.target_secs),
+        &report.target_pretty,
+    );
+    footer_row(
+        &mut html,
+        "overtime",
+        report,
+        |day| signed_secs_to_pretty(day.difference_secs),
+        &report.difference_pretty,
+    );
+    html.push_str("</tfoot>\n</table>\n");
+    html.push_str("<div class=\"signatures\">\n");
+    html.push_str(&format!(
+        "<div class=\"signature\">{}</div>\n",
+        escape(employee.unwrap_or("employee"))
+    ));
+    html.push_str("<div class=\"signature\">approved by</div>\n");
+    html.push_str("<div class=\"signature\">date</div>\n");
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
+
+fn footer_row<F>(html: &mut String, title: &str, report: &Report, cell: F, total: &str)
+where
+    F: Fn(&DayTotal) -> String,
+{
+    html.push_str(&format!("<tr><th class=\"name\">{}</th>", title));
+    for day in report.days.iter() {
+        html.push_str(&format!("<td>{}</td>", cell(day)));
+    }
+    html.push_str(&format!("<td>{}</td></tr>\n", total));
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::{day_start, parse_date};
+    use crate::structs::rounding::Rounding;
+    use crate::structs::task::Activity;
+
+    #[test]
+    fn timesheet_has_days_activities_and_totals() {
+        let monday = parse_date("2026-10-12").unwrap();
+        let nine = day_start(monday) + 9 * 60 * 60;
+        let mut task = Activity::new("<review>");
+        task.stop_at(0);
+        task.start_at(nine);
+        task.stop_at(nine + 8 * 60 * 60);
+        let report = Report::new(
+            &[task],
+            monday,
+            monday + Duration::days(6),
+            &Rounding::default(),
+            7 * 60 * 60 + 30 * 60,
+        )
+        .unwrap();
+        let html = render(&report, Some("Kim & co"));
+        assert!(html.contains("2026-10-12 - 2026-10-18"));
+        assert!(html.contains("Kim &amp; co"));
+        assert!(html.contains("&lt;review&gt;"));
+        assert!(!html.contains("<review>"));
+        assert!(html.contains("<th>Mon<br>2026-10-12</th>"));
+        assert!(html.contains("<td>+00h:30m</td>"));
+        assert!(html.contains("approved by"));
+        assert!(!html.contains("worked"));
+    }
+}
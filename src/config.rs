@@ -1,13 +1,50 @@
+use crate::formats::csv::CsvOptions;
+use crate::formats::standup::StandupOptions;
+use crate::formats::IMPORT_FORMATS;
+use crate::structs::dates::local_date;
+use crate::structs::rounding::{Rounding, RoundingMode, RoundingScope, MAX_MINUTES};
+use crate::structs::snapshot::{Every, SnapshotPolicy};
+use crate::structs::task::sys_now_secs;
 use std::io::prelude::*;
 
 pub const ADDRESS: &str = "127.0.0.1";
 pub const PORT: &str = "8000";
 
+/// What the program does after reading the arguments.
+#[derive(Debug)]
+pub enum Command {
+    /// runs the web server, this is the default.
+    Serve,
+    /// prints the activities as csv and exits.
+    ExportCsv(CsvOptions),
+    /// prints a standup summary and exits.
+    Standup(StandupOptions),
+    /// adds the intervals of an other tracker's export and exits.
+    Import {
+        /// a file, or a Timewarrior data directory.
+        path: std::path::PathBuf,
+        format: String,
+        dry_run: bool,
+    },
+    /// prints the intervals as a timeclock file and exits.
+    ExportTimeclock,
+    /// prints the intervals as an org file and exits.
+    ExportOrg,
+    /// lists the snapshots, or restores one, and exits.
+    Snapshots { restore: Option<String> },
+    /// writes Timewarrior data files to a directory and exits.
+    ExportTimewarrior(std::path::PathBuf),
+}
+
 #[derive(Debug)]
 pub struct Config {
     pub url: String,
     pub port: u16,
     pub dbpath: std::path::PathBuf,
+    pub rounding: Rounding,
+    pub concurrent: bool,
+    pub snapshots: Option<SnapshotPolicy>,
+    pub command: Command,
 }
 
 impl Config {
@@ -37,25 +74,277 @@ impl Config {
             )
             .arg(
                 clap::Arg::new("dbfile")
+                    .global(true)
                     .short('d')
                     .long("dbfile")
-                    .help("File based database path.")
+                    .help("File based database path. Created if it doesn't exist.")
                     .default_value("timetracker.json"),
             )
+            .arg(
+                clap::Arg::new("round-to")
+                    .global(true)
+                    .long("round-to")
+                    .value_name("MINUTES")
+                    .value_parser(clap::value_parser!(u64).range(..=MAX_MINUTES))
+                    .default_value("0")
+                    .help("Round reported times to this many minutes. 0 turns rounding off."),
+            )
+            .arg(
+                clap::Arg::new("round-mode")
+                    .global(true)
+                    .long("round-mode")
+                    .value_parser(["up", "down", "nearest"])
+                    .default_value("nearest")
+                    .help("Direction of the rounding."),
+            )
+            .arg(
+                clap::Arg::new("round-scope")
+                    .global(true)
+                    .long("round-scope")
+                    .value_parser(["interval", "day", "report"])
+                    .default_value("report")
+                    .help("Round every interval, every day or only the totals."),
+            )
+            .arg(
+                clap::Arg::new("round-min")
+                    .global(true)
+                    .long("round-min")
+                    .value_name("MINUTES")
+                    .value_parser(clap::value_parser!(u64).range(..=MAX_MINUTES))
+                    .default_value("0")
+                    .help("Minimum billable time for any tracked activity."),
+            )
+            .arg(
+                clap::Arg::new("concurrent")
+                    .global(true)
+                    .long("concurrent")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Let all activities run at the same time."),
+            )
+            .arg(
+                clap::Arg::new("snapshots")
+                    .global(true)
+                    .long("snapshots")
+                    .value_parser(["off", "hourly", "daily"])
+                    .default_value("off")
+                    .help("How often the server copies the db into <dbfile>.snapshots, off by default."),
+            )
+            .arg(
+                clap::Arg::new("snapshot-keep")
+                    .global(true)
+                    .long("snapshot-keep")
+                    .value_name("COUNT")
+                    .value_parser(clap::value_parser!(u64).range(1..))
+                    .default_value("14")
+                    .help("Number of snapshots kept, older ones are removed."),
+            )
+            .subcommand(
+                clap::Command::new("export-csv")
+                    .about("Print the tracked time as csv, like /api/export.csv.")
+                    .arg(
+                        clap::Arg::new("rows")
+                            .long("rows")
+                            .value_parser(["interval", "day"])
+                            .help("One row per interval or per activity and day."),
+                    )
+                    .arg(
+                        clap::Arg::new("columns")
+                            .long("columns")
+                            .value_name("COLUMNS")
+                            .help(
+                                "Comma separated columns out of date, name, project, \
+                                start, end, secs, hours, duration and billable.",
+                            ),
+                    )
+                    .arg(
+                        clap::Arg::new("delimiter")
+                            .long("delimiter")
+                            .help("Field delimiter, a single character or tab."),
+                    )
+                    .arg(
+                        clap::Arg::new("from")
+                            .long("from")
+                            .value_name("YYYY-MM-DD")
+                            .help("First date to export."),
+                    )
+                    .arg(
+                        clap::Arg::new("to")
+                            .long("to")
+                            .value_name("YYYY-MM-DD")
+                            .help("Last date to export."),
+                    ),
+            )
+            .subcommand(
+                clap::Command::new("standup")
+                    .about("Print a summary for a standup, like /api/standup.")
+                    .arg(
+                        clap::Arg::new("range")
+                            .long("range")
+                            .value_name("RANGE")
+                            .help("yesterday, today, week, lastweek or a YYYY-MM-DD date."),
+                    )
+                    .arg(
+                        clap::Arg::new("from")
+                            .long("from")
+                            .value_name("YYYY-MM-DD")
+                            .help("First date of the summary."),
+                    )
+                    .arg(
+                        clap::Arg::new("to")
+                            .long("to")
+                            .value_name("YYYY-MM-DD")
+                            .help("Last date of the summary."),
+                    )
+                    .arg(
+                        clap::Arg::new("format")
+                            .long("format")
+                            .value_parser(["markdown", "text"])
+                            .help("Markdown bullets or the text layout of /api/pretty."),
+                    ),
+            )
+            .subcommand(
+                clap::Command::new("import")
+                    .about("Add the time of an other tracker's export, like /api/import.")
+                    .arg(
+                        clap::Arg::new("file")
+                            .value_name("FILE")
+                            .required(true)
+                            .help("The export, or Timewarrior's data directory."),
+                    )
+                    .arg(
+                        clap::Arg::new("format")
+                            .long("format")
+                            .value_parser(IMPORT_FORMATS)
+                            .default_value("auto")
+                            .help("Where the export comes from."),
+                    )
+                    .arg(
+                        clap::Arg::new("dry-run")
+                            .long("dry-run")
+                            .action(clap::ArgAction::SetTrue)
+                            .help("Only print what would be added."),
+                    ),
+            )
+            .subcommand(clap::Command::new("export-timeclock").about(
+                "Print the intervals as ledger timeclock entries, like /api/export.timeclock.",
+            ))
+            .subcommand(
+                clap::Command::new("export-org")
+                    .about("Print an org file with CLOCK lines, like /api/export.org."),
+            )
+            .subcommand(
+                clap::Command::new("snapshots")
+                    .about("List the snapshots of the db, oldest first.")
+                    .arg(
+                        clap::Arg::new("restore")
+                            .long("restore")
+                            .value_name("NAME")
+                            .help(
+                                "Restore a snapshot, the replaced state is kept in <dbfile>.<time>.bak.",
+                            ),
+                    ),
+            )
+            .subcommand(
+                clap::Command::new("export-timewarrior")
+                    .about("Write the intervals as Timewarrior data files, one per month.")
+                    .arg(
+                        clap::Arg::new("dir")
+                            .value_name("DIR")
+                            .required(true)
+                            .help("Directory for the files, existing files are not replaced."),
+                    ),
+            )
             .get_matches();
         let url = matches.get_one::<String>("url").unwrap().to_owned();
         let port = *matches.get_one::<u16>("port").unwrap();
         let dbfile = matches.get_one::<String>("dbfile").unwrap().to_string();
         let mut dbpath = std::path::PathBuf::new();
         dbpath.push(dbfile);
-        std::fs::File::create(&dbpath)
-            .unwrap()
-            .write_all(b"[]")
-            .unwrap();
-        Config { url, port, dbpath }
+        if !dbpath.exists() {
+            std::fs::File::create(&dbpath)
+                .unwrap()
+                .write_all(b"[]")
+                .unwrap();
+        }
+        let rounding = Rounding {
+            increment_secs: *matches.get_one::<u64>("round-to").unwrap() * 60,
+            mode: matches
+                .get_one::<String>("round-mode")
+                .unwrap()
+                .parse::<RoundingMode>()
+                .unwrap(),
+            scope: matches
+                .get_one::<String>("round-scope")
+                .unwrap()
+                .parse::<RoundingScope>()
+                .unwrap(),
+            minimum_secs: *matches.get_one::<u64>("round-min").unwrap() * 60,
+        };
+        let concurrent = matches.get_flag("concurrent");
+        let snapshots = match matches.get_one::<String>("snapshots").unwrap().as_str() {
+            "off" => None,
+            every => Some(SnapshotPolicy {
+                every: every.parse::<Every>().unwrap(),
+                keep: *matches.get_one::<u64>("snapshot-keep").unwrap() as usize,
+            }),
+        };
+        let command = match matches.subcommand() {
+            Some(("export-csv", args)) => {
+                let arg = |name: &str| args.get_one::<String>(name).map(|s| s.as_str());
+                let options = CsvOptions::parse(
+                    arg("rows"),
+                    arg("columns"),
+                    arg("delimiter"),
+                    arg("from"),
+                    arg("to"),
+                )
+                .unwrap_or_else(|error| exit_with_error(&error));
+                Command::ExportCsv(options)
+            }
+            Some(("standup", args)) => {
+                let arg = |name: &str| args.get_one::<String>(name).map(|s| s.as_str());
+                let options = StandupOptions::parse(
+                    arg("range"),
+                    arg("from"),
+                    arg("to"),
+                    arg("format"),
+                    local_date(sys_now_secs()),
+                )
+                .unwrap_or_else(|error| exit_with_error(&error));
+                Command::Standup(options)
+            }
+            Some(("import", args)) => Command::Import {
+                path: std::path::PathBuf::from(args.get_one::<String>("file").unwrap()),
+                format: args.get_one::<String>("format").unwrap().to_owned(),
+                dry_run: args.get_flag("dry-run"),
+            },
+            Some(("export-timeclock", _)) => Command::ExportTimeclock,
+            Some(("export-org", _)) => Command::ExportOrg,
+            Some(("snapshots", args)) => Command::Snapshots {
+                restore: args.get_one::<String>("restore").cloned(),
+            },
+            Some(("export-timewarrior", args)) => Command::ExportTimewarrior(
+                std::path::PathBuf::from(args.get_one::<String>("dir").unwrap()),
+            ),
+            _ => Command::Serve,
+        };
+        Config {
+            url,
+            port,
+            dbpath,
+            rounding,
+            concurrent,
+            snapshots,
+            command,
+        }
     }
 }
 
+fn exit_with_error(error: &dyn std::fmt::Display) -> ! {
+    eprintln!("error: {}", error);
+    std::process::exit(2)
+}
+
 pub fn get_congig() -> Config {
     Config::new()
 }
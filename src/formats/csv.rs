@@ -0,0 +1,401 @@
+use crate::structs::dates::{day_start, local_date, parse_date};
+use crate::structs::error::TrackerError;
+use crate::structs::rounding::Rounding;
+use crate::structs::task::*;
+use chrono::{Local, NaiveDate, TimeZone};
+
+/// What one row of the export stands for.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Rows {
+    /// every start - stop interval.
+    Interval,
+    /// the time of one activity on one day.
+    Day,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Column {
+    Date,
+    Name,
+    Project,
+    /// first start, for day rows the first start of the day.
+    Start,
+    /// last stop, for day rows the last stop of the day.
+    End,
+    Secs,
+    /// decimal hours like 1.25
+    Hours,
+    /// 01h:15m like in the pretty output.
+    Duration,
+    /// decimal hours after rounding.
+    Billable,
+}
+
+impl std::str::FromStr for Column {
+    type Err = TrackerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "date" => Ok(Column::Date),
+            "name" => Ok(Column::Name),
+            "project" => Ok(Column::Project),
+            "start" => Ok(Column::Start),
+            "end" => Ok(Column::End),
+            "secs" => Ok(Column::Secs),
+            "hours" => Ok(Column::Hours),
+            "duration" => Ok(Column::Duration),
+            "billable" => Ok(Column::Billable),
+            _ => Err(TrackerError::InvalidValue(format!("unknown column: {}", s))),
+        }
+    }
+}
+
+impl Column {
+    fn header(&self) -> &'static str {
+        match self {
+            Column::Date => "date",
+            Column::Name => "name",
+            Column::Project => "project",
+            Column::Start => "start",
+            Column::End => "end",
+            Column::Secs => "secs",
+            Column::Hours => "hours",
+            Column::Duration => "duration",
+            Column::Billable => "billable",
+        }
+    }
+}
+
+/// Settings of a csv export. Every field can be given as text
+/// so the cli and the api share the parsing.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CsvOptions {
+    pub rows: Rows,
+    pub columns: Vec<Column>,
+    pub delimiter: char,
+    /// first date included, no limit if None.
+    pub from: Option<NaiveDate>,
+    /// last date included, no limit if None.
+    pub to: Option<NaiveDate>,
+}
+
+impl CsvOptions {
+    pub fn parse(
+        rows: Option<&str>,
+        columns: Option<&str>,
+        delimiter: Option<&str>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Self, TrackerError> {
+        let rows = match rows.unwrap_or("interval") {
+            "interval" => Rows::Interval,
+            "day" => Rows::Day,
+            other => {
+                return Err(TrackerError::InvalidValue(format!(
+                    "rows can be interval or day, got: {}",
+                    other
+                )))
+            }
+        };
+        let columns = match columns {
+            Some(columns) => columns
+                .split(',')
+                .map(|column| column.parse::<Column>())
+                .collect::<Result<Vec<Column>, TrackerError>>()?,
+            None => match rows {
+                Rows::Interval => vec![
+                    Column::Date,
+                    Column::Start,
+                    Column::End,
+                    Column::Name,
+                    Column::Project,
+                    Column::Hours,
+                ],
+                Rows::Day => vec![Column::Date, Column::Name, Column::Project, Column::Hours],
+            },
+        };
+        let delimiter = match delimiter.unwrap_or(",") {
+            "tab" | "\\t" => '\t',
+            text if text.chars().count() == 1 => text.chars().next().unwrap(),
+            text => {
+                return Err(TrackerError::InvalidValue(format!(
+                    "delimiter must be a single character, got: {}",
+                    text
+                )))
+            }
+        };
+        let from = from.map(parse_date).transpose()?;
+        let to = to.map(parse_date).transpose()?;
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(TrackerError::InvalidTime(format!(
+                    "from {} is after to {}",
+                    from, to
+                )));
+            }
+        }
+        Ok(Self {
+            rows,
+            columns,
+            delimiter,
+            from,
+            to,
+        })
+    }
+}
+
+/// One line of the export before it's formatted.
+struct Row {
+    name: String,
+    project: Option<String>,
+    start: SecType,
+    end: SecType,
+    intervals: Vec<Interval>,
+}
+
+/// Renders the activities as csv with a header line.
+pub fn export(tasks: &[Activity], options: &CsvOptions, rounding: &Rounding) -> String {
+    let range_start = options.from.map(day_start).unwrap_or(0);
+    // nothing comes after the last date chrono knows.
+    let range_end = options
+        .to
+        .and_then(|to| to.succ_opt())
+        .map(day_start)
+        .unwrap_or(SecType::MAX);
+
+    let mut rows: Vec<Row> = Vec::new();
+    for task in tasks {
+        let intervals = task.intervals_between(range_start, range_end);
+        match options.rows {
+            Rows::Interval => rows.extend(intervals.iter().map(|interval| Row {
+                name: task.name(),
+                project: task.project(),
+                start: interval.start,
+                end: interval.end,
+                intervals: vec![*interval],
+            })),
+            Rows::Day => {
+                let mut days: Vec<Row> = Vec::new();
+                for interval in intervals.into_iter().flat_map(split_days) {
+                    match days.last_mut() {
+                        Some(day) if local_date(day.start) == local_date(interval.start) => {
+                            day.end = interval.end;
+                            day.intervals.push(interval);
+                        }
+                        _ => days.push(Row {
+                            name: task.name(),
+                            project: task.project(),
+                            start: interval.start,
+                            end: interval.end,
+                            intervals: vec![interval],
+                        }),
+                    }
+                }
+                rows.extend(days);
+            }
+        }
+    }
+    rows.sort_by(|a, b| a.start.cmp(&b.start).then(a.name.cmp(&b.name)));
+
+    let delimiter = options.delimiter.to_string();
+    let mut result = options
+        .columns
+        .iter()
+        .map(|column| column.header().to_string())
+        .collect::<Vec<String>>()
+        .join(&delimiter);
+    result.push('\n');
+    for row in rows {
+        let secs: SecType = row.intervals.iter().map(|i| i.secs()).sum();
+        let fields: Vec<String> = options
+            .columns
+            .iter()
+            .map(|column| match column {
+                Column::Date => local_date(row.start).format("%Y-%m-%d").to_string(),
+                Column::Name => row.name.to_owned(),
+                Column::Project => row.project.to_owned().unwrap_or_default(),
+                Column::Start => local_datetime(row.start),
+                Column::End => local_datetime(row.end),
+                Column::Secs => secs.to_string(),
+                Column::Hours => decimal_hours(secs),
+                Column::Duration => secs_to_pretty(secs),
+                Column::Billable => decimal_hours(rounding.billable(&row.intervals)),
+            })
+            .map(|field| escape(&field, options.delimiter))
+            .collect();
+        result.push_str(&fields.join(&delimiter));
+        result.push('\n');
+    }
+    result
+}
+
+/// the parts of an interval on each local day it touches, cut at
+/// midnight like the reports do.
+fn split_days(interval: Interval) -> Vec<Interval> {
+    let mut parts = Vec::new();
+    let mut start = interval.start;
+    while let Some(part) = interval.clip(start, next_midnight(start)) {
+        start = part.end;
+        parts.push(part);
+    }
+    parts
+}
+
+fn next_midnight(secs: SecType) -> SecType {
+    local_date(secs)
+        .succ_opt()
+        .map(day_start)
+        .unwrap_or(SecType::MAX)
+}
+
+fn local_datetime(secs: SecType) -> String {
+    Local
+        .timestamp_opt(secs as i64, 0)
+        .unwrap()
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn decimal_hours(secs: SecType) -> String {
+    format!("{:.2}", secs as f64 / 3600.0)
+}
+
+/// quotes a field if it has the delimiter, quotes or line breaks in it.
+pub fn escape(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter) || field.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// splits csv text into rows of fields. Quoted fields can hold the
+/// delimiter, doubled quotes and line breaks.
+pub fn parse(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            c if c == delimiter && !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| !(row.len() == 1 && row[0].is_empty()));
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task(name: &str, intervals: &[(SecType, SecType)]) -> Activity {
+        let mut task = Activity::new(name);
+        task.stop_at(0);
+        for (start, end) in intervals {
+            task.start_at(*start);
+            task.stop_at(*end);
+        }
+        task
+    }
+
+    #[test]
+    fn parsing_options() {
+        let options = CsvOptions::parse(Some("day"), Some("name,secs"), Some(";"), None, None);
+        let options = options.unwrap();
+        assert_eq!(options.rows, Rows::Day);
+        assert_eq!(options.columns, vec![Column::Name, Column::Secs]);
+        assert_eq!(options.delimiter, ';');
+        assert!(CsvOptions::parse(Some("week"), None, None, None, None).is_err());
+        assert!(CsvOptions::parse(None, Some("name,colour"), None, None, None).is_err());
+        assert!(CsvOptions::parse(None, None, Some(";;"), None, None).is_err());
+        assert!(CsvOptions::parse(None, None, None, Some("2026-13-01"), None).is_err());
+        assert!(
+            CsvOptions::parse(None, None, None, Some("2026-10-14"), Some("2026-10-13")).is_err()
+        );
+        let last = NaiveDate::MAX.format("%Y-%m-%d").to_string();
+        let options = CsvOptions::parse(None, None, None, None, Some(&last)).unwrap();
+        assert_eq!(
+            export(&[], &options, &Rounding::default()).lines().count(),
+            1
+        );
+    }
+
+    #[test]
+    fn escaping_fields() {
+        assert_eq!(escape("plain", ','), "plain");
+        assert_eq!(escape("a,b", ','), "\"a,b\"");
+        assert_eq!(escape("a,b", ';'), "a,b");
+        assert_eq!(escape("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn parsing_csv() {
+        let text = "\u{feff}a,b,c\r\n\"x, y\",\"say \"\"hi\"\"\",\"two\nlines\"\n\n1,,3";
+        assert_eq!(
+            parse(text, ','),
+            vec![
+                vec!["a", "b", "c"],
+                vec!["x, y", "say \"hi\"", "two\nlines"],
+                vec!["1", "", "3"],
+            ]
+        );
+        assert_eq!(parse("a;b\n", ';'), vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn interval_and_day_rows() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let nine = day_start(date) + 9 * 60 * 60;
+        let tasks = vec![
+            task(
+                "alpha, the first",
+                &[(nine, nine + 1800), (nine + 3600, nine + 5400)],
+            ),
+            task("beta", &[(nine + 1800, nine + 3600)]),
+        ];
+        let options = CsvOptions::parse(None, Some("name,secs"), None, None, None).unwrap();
+        assert_eq!(
+            export(&tasks, &options, &Rounding::default()),
+            "name,secs\n\"alpha, the first\",1800\nbeta,1800\n\"alpha, the first\",1800\n"
+        );
+        let options =
+            CsvOptions::parse(Some("day"), Some("date,name,hours"), Some(";"), None, None);
+        assert_eq!(
+            export(&tasks, &options.unwrap(), &Rounding::default()),
+            "date;name;hours\n2026-10-12;alpha, the first;1.00\n2026-10-12;beta;0.50\n"
+        );
+        let late = day_start(date) + 23 * 60 * 60;
+        let night = [task("night", &[(late, late + 2 * 60 * 60)])];
+        let options = CsvOptions::parse(Some("day"), Some("date,secs"), None, None, None);
+        assert_eq!(
+            export(&night, &options.unwrap(), &Rounding::default()),
+            "date,secs\n2026-10-12,3600\n2026-10-13,3600\n"
+        );
+        let options =
+            CsvOptions::parse(None, None, None, Some("2026-10-13"), Some("2026-10-14")).unwrap();
+        assert_eq!(
+            export(&tasks, &options, &Rounding::default())
+                .lines()
+                .count(),
+            1
+        );
+    }
+}
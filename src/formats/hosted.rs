@@ -0,0 +1,211 @@
+//! Reads the detailed csv exports of hosted trackers, Toggl and Clockify.
+
+use crate::formats::csv;
+use crate::structs::dates::local_to_secs;
+use crate::structs::error::TrackerError;
+use crate::structs::import::{ImportEntry, ImportFile, UNNAMED};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Source {
+    Toggl,
+    Clockify,
+}
+
+impl std::str::FromStr for Source {
+    type Err = TrackerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "toggl" => Ok(Source::Toggl),
+            "clockify" => Ok(Source::Clockify),
+            _ => Err(TrackerError::InvalidValue(format!(
+                "format can be {}, got: {}",
+                super::IMPORT_FORMATS.join(", "),
+                s
+            ))),
+        }
+    }
+}
+
+impl Source {
+    /// Toggl writes "Start date", Clockify "Start Date".
+    pub fn detect(header: &str) -> Option<Self> {
+        if header.contains("Start date") {
+            Some(Source::Toggl)
+        } else if header.contains("Start Date") {
+            Some(Source::Clockify)
+        } else {
+            None
+        }
+    }
+
+    /// Clockify writes dates the way the account is set up,
+    /// month first is its default.
+    fn date_formats(&self) -> &'static [&'static str] {
+        match self {
+            Source::Toggl => &["%Y-%m-%d"],
+            Source::Clockify => &["%m/%d/%Y", "%Y-%m-%d", "%d.%m.%Y"],
+        }
+    }
+}
+
+/// reads a format option, auto or nothing leaves the detection to parse.
+pub fn source(format: Option<&str>) -> Result<Option<Source>, TrackerError> {
+    match format {
+        None | Some("auto") => Ok(None),
+        Some(format) => format.parse().map(Some),
+    }
+}
+
+/// The entries of an export. Without a source it is detected from
+/// the header line. Rows that can't be read are skipped.
+pub fn parse(text: &str, source: Option<Source>) -> Result<ImportFile, TrackerError> {
+    let rows = csv::parse(text, ',');
+    let header = match rows.first() {
+        Some(header) => header,
+        None => return Ok(ImportFile::default()),
+    };
+    let source = match source.or_else(|| Source::detect(&header.join(","))) {
+        Some(source) => source,
+        None => {
+            return Err(TrackerError::InvalidValue(
+                "not a Toggl or Clockify csv export".to_string(),
+            ))
+        }
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|title| title.trim().eq_ignore_ascii_case(name))
+    };
+    let required = |name: &str| {
+        column(name).ok_or_else(|| TrackerError::InvalidValue(format!("missing column: {}", name)))
+    };
+    let start_date = required("start date")?;
+    let start_time = required("start time")?;
+    let end_date = required("end date")?;
+    let end_time = required("end time")?;
+    let description = column("description");
+    let task = column("task");
+    let project = column("project");
+    let tags = column("tags");
+
+    let mut file = ImportFile::default();
+    for (number, row) in rows.iter().enumerate().skip(1) {
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| row.get(index))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let times = || -> Result<(u64, u64), TrackerError> {
+            let start = datetime(source, field(Some(start_date)), field(Some(start_time)))?;
+            let end = datetime(source, field(Some(end_date)), field(Some(end_time)))?;
+            Ok((start, end))
+        };
+        let (start, end) = match times() {
+            Ok(times) => times,
+            Err(error) => {
+                file.skipped.push(format!(
+                    "line {}: {} ({})",
+                    number + 1,
+                    row.join(","),
+                    error
+                ));
+                continue;
+            }
+        };
+        let name = field(description)
+            .or_else(|| field(task))
+            .or_else(|| field(project))
+            .unwrap_or(UNNAMED);
+        file.entries.push(ImportEntry {
+            name: name.to_string(),
+            project: field(project).map(String::from),
+            tags: field(tags)
+                .map(|tags| {
+                    tags.split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            notes: None,
+            start,
+            end,
+            precision: 1,
+        });
+    }
+    Ok(file)
+}
+
+fn datetime(source: Source, date: Option<&str>, time: Option<&str>) -> Result<u64, TrackerError> {
+    let (date, time) = match (date, time) {
+        (Some(date), Some(time)) => (date, time),
+        _ => {
+            return Err(TrackerError::InvalidTime(
+                "missing date or time".to_string(),
+            ))
+        }
+    };
+    let parsed_date = source
+        .date_formats()
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| TrackerError::InvalidTime(format!("can't read date: {}", date)))?;
+    let parsed_time = ["%H:%M:%S", "%I:%M:%S %p", "%H:%M", "%I:%M %p"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(time, format).ok())
+        .ok_or_else(|| TrackerError::InvalidTime(format!("can't read time: {}", time)))?;
+    local_to_secs(
+        &NaiveDateTime::new(parsed_date, parsed_time),
+        &format!("{} {}", date, time),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::parse_at;
+
+    #[test]
+    fn toggl_export() {
+        let text = "User,Email,Client,Project,Task,Description,Billable,Start date,Start time,\
+            End date,End time,Duration,Tags,Amount ()\n\
+            Kim,kim@example.com,Acme,Website,,\"Fix login, again\",Yes,2026-10-12,09:00:00,\
+            2026-10-12,10:30:00,01:30:00,\"backend, urgent\",\n\
+            Kim,kim@example.com,,,,,No,2026-10-12,11:00:00,2026-10-12,11:15:00,00:15:00,,\n";
+        let entries = parse(text, None).unwrap().entries;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "Fix login, again");
+        assert_eq!(entries[0].project, Some("Website".to_string()));
+        assert_eq!(entries[0].tags, ["backend", "urgent"]);
+        assert_eq!(entries[0].start, parse_at("2026-10-12 09:00", 0).unwrap());
+        assert_eq!(entries[0].end - entries[0].start, 90 * 60);
+        assert_eq!(entries[1].name, UNNAMED);
+        assert_eq!(entries[1].project, None);
+    }
+
+    #[test]
+    fn clockify_export() {
+        let text = "Project,Client,Description,Task,User,Group,Email,Tags,Billable,Start Date,\
+            Start Time,End Date,End Time,Duration (h),Duration (decimal)\n\
+            Website,Acme,,Design,Kim,,kim@example.com,design,Yes,10/12/2026,01:00:00 PM,\
+            10/12/2026,02:00:00 PM,01:00:00,1.00\n";
+        let entries = parse(text, None).unwrap().entries;
+        assert_eq!(entries[0].name, "Design");
+        assert_eq!(entries[0].start, parse_at("2026-10-12 13:00", 0).unwrap());
+        assert_eq!(entries[0].end - entries[0].start, 3600);
+
+        assert!(parse("a,b\n1,2\n", None).is_err());
+        assert_eq!(source(Some("auto")).unwrap(), None);
+        assert!(source(Some("harvest")).is_err());
+        let broken = text.replace("10/12/2026,01:00:00 PM", "12th,01:00:00 PM");
+        let file = parse(&broken, Some(Source::Clockify)).unwrap();
+        assert!(file.entries.is_empty());
+        assert_eq!(file.skipped.len(), 1);
+        assert!(file.skipped[0].starts_with("line 2: Website,Acme"));
+        assert!(file.skipped[0].ends_with("(invalid time: can't read date: 12th)"));
+    }
+}
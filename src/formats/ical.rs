@@ -0,0 +1,224 @@
+use crate::structs::dates::{day_start, parse_date};
+use crate::structs::error::TrackerError;
+use crate::structs::task::*;
+use chrono::{NaiveDate, TimeZone, Utc};
+
+/// lines longer than this many octets are folded.
+const LINE_OCTETS: usize = 75;
+
+/// Filters of an iCalendar export.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct IcalOptions {
+    /// only activities of this project.
+    pub project: Option<String>,
+    /// first date included, no limit if None.
+    pub from: Option<NaiveDate>,
+    /// last date included, no limit if None.
+    pub to: Option<NaiveDate>,
+}
+
+impl IcalOptions {
+    pub fn parse(
+        project: Option<&str>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Self, TrackerError> {
+        Ok(Self {
+            project: project
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty()),
+            from: from.map(parse_date).transpose()?,
+            to: to.map(parse_date).transpose()?,
+        })
+    }
+}
+
+/// Renders every interval as an event of a calendar, `now` is
+/// the timestamp of the events.
+pub fn export(tasks: &[Activity], options: &IcalOptions, now: SecType) -> String {
+    let range_start = options.from.map(day_start).unwrap_or(0);
+    let range_end = options
+        .to
+        .and_then(|to| to.succ_opt())
+        .map(day_start)
+        .unwrap_or(SecType::MAX);
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//timetracker//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:timetracker".to_string(),
+    ];
+    for task in tasks {
+        if options.project.is_some() && task.project() != options.project {
+            continue;
+        }
+        for interval in task.intervals_between(range_start, range_end) {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!(
+                "UID:{}-{}@timetracker",
+                interval.start,
+                uid_part(&task.name())
+            ));
+            lines.push(format!("DTSTAMP:{}", utc_datetime(now)));
+            lines.push(format!("DTSTART:{}", utc_datetime(interval.start)));
+            lines.push(format!("DTEND:{}", utc_datetime(interval.end)));
+            lines.push(format!("SUMMARY:{}", escape(&task.name())));
+            if let Some(description) = description(task) {
+                lines.push(format!("DESCRIPTION:{}", escape(&description)));
+            }
+            if !task.tags().is_empty() {
+                let tags: Vec<String> = task.tags().iter().map(|tag| escape(tag)).collect();
+                lines.push(format!("CATEGORIES:{}", tags.join(",")));
+            }
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut result = String::new();
+    for line in lines {
+        result.push_str(&fold(&line));
+        result.push_str("\r\n");
+    }
+    result
+}
+
+/// the notes, then the project and tags.
+fn description(task: &Activity) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(notes) = task.notes() {
+        parts.push(notes);
+    }
+    if let Some(project) = task.project() {
+        parts.push(format!("project: {}", project));
+    }
+    if !task.tags().is_empty() {
+        parts.push(format!("tags: {}", task.tags().join(", ")));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("\n"))
+    }
+}
+
+fn utc_datetime(secs: SecType) -> String {
+    Utc.timestamp_opt(secs as i64, 0)
+        .unwrap()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// a 64 bit FNV-1a hash of the activity name in hex. Unlike the
+/// std hasher it stays the same between builds, so calendars that
+/// subscribe to the export keep recognizing the events.
+fn uid_part(name: &str) -> String {
+    let hash = name.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// escapes a TEXT value.
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// splits a content line so no part is longer than 75 octets,
+/// the continuation lines start with a space.
+pub fn fold(line: &str) -> String {
+    let mut result = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > LINE_OCTETS {
+            result.push_str("\r\n ");
+            octets = 1;
+        }
+        result.push(c);
+        octets += c.len_utf8();
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task(name: &str, project: Option<&str>, intervals: &[(SecType, SecType)]) -> Activity {
+        let mut task = Activity::new(name);
+        if let Some(project) = project {
+            task.set_project(project);
+        }
+        task.stop_at(0);
+        for (start, end) in intervals {
+            task.start_at(*start);
+            task.stop_at(*end);
+        }
+        task
+    }
+
+    #[test]
+    fn escaping_and_folding() {
+        assert_eq!(escape("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+        let line = format!("SUMMARY:{}", "x".repeat(100));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(
+            fold("é".repeat(50).as_str())
+                .split("\r\n")
+                .next()
+                .unwrap()
+                .len(),
+            74
+        );
+    }
+
+    #[test]
+    fn intervals_become_events() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let nine = day_start(date) + 9 * 60 * 60;
+        let mut alpha = task("alpha", Some("billing"), &[(nine, nine + 1800)]);
+        alpha.set_notes("fixing, testing");
+        alpha.set_tags(&["review".to_string()]);
+        let tasks = vec![alpha, task("beta", None, &[(nine + 1800, nine + 3600)])];
+
+        let calendar = export(&tasks, &IcalOptions::default(), nine + 3600);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains(&format!("DTSTART:{}\r\n", utc_datetime(nine))));
+        assert!(calendar.contains("SUMMARY:alpha\r\n"));
+        assert!(calendar
+            .contains("DESCRIPTION:fixing\\, testing\\nproject: billing\\ntags: review\r\n"));
+        assert!(calendar.contains("CATEGORIES:review\r\n"));
+
+        let options = IcalOptions::parse(Some("billing"), None, None).unwrap();
+        let calendar = export(&tasks, &options, nine + 3600);
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(!calendar.contains("SUMMARY:beta"));
+
+        let options = IcalOptions::parse(None, Some("2026-10-13"), None).unwrap();
+        let calendar = export(&tasks, &options, nine + 3600);
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 0);
+        assert!(IcalOptions::parse(None, Some("yesterday"), None).is_err());
+    }
+
+    #[test]
+    fn uids_differ_by_name() {
+        let names = ["a b", "a-b", "a_b", "äb", "öb"];
+        let mut uids: Vec<String> = names.iter().map(|name| uid_part(name)).collect();
+        uids.sort();
+        uids.dedup();
+        assert_eq!(uids.len(), names.len());
+        assert_eq!(uid_part(""), "cbf29ce484222325");
+    }
+}
@@ -0,0 +1,83 @@
+pub mod csv;
+pub mod hosted;
+pub mod ical;
+pub mod org;
+pub mod prometheus;
+pub mod standup;
+pub mod timeclock;
+pub mod timewarrior;
+
+use crate::structs::error::TrackerError;
+use crate::structs::import::ImportFile;
+
+/// formats `import` reads, auto detects them from the text.
+pub const IMPORT_FORMATS: [&str; 6] = [
+    "auto",
+    "toggl",
+    "clockify",
+    "timewarrior",
+    "timeclock",
+    "org",
+];
+
+/// The entries of an other tracker's export. `known` are the names of
+/// the stored activities, Timewarrior's tags are matched against them.
+pub fn import_entries(
+    text: &str,
+    format: Option<&str>,
+    known: &[String],
+) -> Result<ImportFile, TrackerError> {
+    let format = match format {
+        None | Some("auto") => detect(text),
+        Some(format) => format,
+    };
+    match format {
+        "timewarrior" => timewarrior::parse(text, known).map(ImportFile::from),
+        "timeclock" => timeclock::parse(text).map(ImportFile::from),
+        "org" => org::parse(text).map(ImportFile::from),
+        format => hosted::parse(text, hosted::source(Some(format))?),
+    }
+}
+
+/// org files have CLOCK lines, the other formats are told apart by
+/// the first line that isn't a comment. csv by default.
+fn detect(text: &str) -> &'static str {
+    if text
+        .lines()
+        .any(|line| line.trim_start().starts_with("CLOCK: ["))
+    {
+        return "org";
+    }
+    let first = text
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with([';', '#', '*']))
+        .unwrap_or_default();
+    if first.starts_with("inc ") {
+        "timewarrior"
+    } else if first.starts_with("i ") {
+        "timeclock"
+    } else {
+        "auto"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detecting_formats() {
+        assert_eq!(detect("inc 20261014T090000Z # review\n"), "timewarrior");
+        assert_eq!(
+            detect("; ledger\ni 2026/10/18 09:00:00 review\n"),
+            "timeclock"
+        );
+        assert_eq!(
+            detect("#+TITLE: x\n* review\n  CLOCK: [2026-10-18 Sun 09:00]\n"),
+            "org"
+        );
+        assert_eq!(detect("Project,Start date\n"), "auto");
+        assert!(import_entries("i 2026/10/18 09:00:00 a\n", Some("harvest"), &[]).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! Org-mode files with one heading per activity, nested under a heading
+//! per project, and a CLOCK line per interval in the LOGBOOK drawer:
+//!
+//!     * billing
+//!     ** review                                          :backend:
+//!        :LOGBOOK:
+//!        CLOCK: [2026-10-18 Sun 09:00]--[2026-10-18 Sun 10:00] =>  1:00
+//!        :END:
+//!        the notes
+//!
+//! Org clocks have minutes, seconds are cut off.
+
+use crate::structs::dates::local_to_secs;
+use crate::structs::error::TrackerError;
+use crate::structs::import::ImportEntry;
+use crate::structs::task::*;
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use std::collections::BTreeMap;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %a %H:%M";
+const INDENT: &str = "   ";
+
+/// Activities of a project under the project's heading, the others
+/// on the top level after them. A running activity has an open clock.
+pub fn export(tasks: &[Activity]) -> String {
+    let mut projects: BTreeMap<String, Vec<&Activity>> = BTreeMap::new();
+    let mut loose = Vec::new();
+    for task in tasks {
+        match task.project() {
+            Some(project) => projects.entry(project).or_default().push(task),
+            None => loose.push(task),
+        }
+    }
+
+    let mut result = String::new();
+    for (project, tasks) in projects {
+        result.push_str(&format!("* {}\n", project));
+        for task in tasks {
+            result.push_str(&entry(task, 2));
+        }
+    }
+    for task in loose {
+        result.push_str(&entry(task, 1));
+    }
+    result
+}
+
+fn entry(task: &Activity, level: usize) -> String {
+    let mut heading = format!("{} {}", "*".repeat(level), task.name());
+    if !task.tags().is_empty() {
+        let tags: Vec<String> = task
+            .tags()
+            .iter()
+            .map(|tag| tag.replace(' ', "_"))
+            .collect();
+        heading.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    let mut result = format!("{}\n", heading);
+    let intervals = task.intervals();
+    if !intervals.is_empty() {
+        result.push_str(&format!("{}:LOGBOOK:\n", INDENT));
+        // newest first, like org adds them.
+        for (index, interval) in intervals.iter().enumerate().rev() {
+            let running = task.is_active() && index == intervals.len() - 1;
+            let start = interval.start - interval.start % 60;
+            if running {
+                result.push_str(&format!("{}CLOCK: {}\n", INDENT, timestamp(start)));
+                continue;
+            }
+            let end = interval.end - interval.end % 60;
+            let minutes = (end.saturating_sub(start)) / 60;
+            result.push_str(&format!(
+                "{}CLOCK: {}--{} => {:>2}:{:02}\n",
+                INDENT,
+                timestamp(start),
+                timestamp(end),
+                minutes / 60,
+                minutes % 60
+            ));
+        }
+        result.push_str(&format!("{}:END:\n", INDENT));
+    }
+    if let Some(notes) = task.notes() {
+        for line in notes.lines() {
+            result.push_str(&format!("{}{}\n", INDENT, line));
+        }
+    }
+    result
+}
+
+/// The closed clocks of an org file. A heading with clocks is the
+/// activity, its parent heading the project. Text under the heading
+/// outside of drawers is read as the notes.
+pub fn parse(text: &str) -> Result<Vec<ImportEntry>, TrackerError> {
+    let mut entries: Vec<ImportEntry> = Vec::new();
+    // titles of the headings above the current line.
+    let mut path: Vec<String> = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut notes: Vec<String> = Vec::new();
+    let mut first_entry = 0;
+    let mut in_drawer = false;
+    for (number, line) in text.lines().enumerate() {
+        let level = line.chars().take_while(|c| *c == '*').count();
+        if level > 0 && line[level..].starts_with(' ') {
+            finish_notes(&mut entries[first_entry..], &mut notes);
+            first_entry = entries.len();
+            let (title, heading_tags) = heading(&line[level..]);
+            path.truncate(level - 1);
+            while path.len() < level - 1 {
+                path.push(String::new());
+            }
+            path.push(title);
+            tags = heading_tags;
+            in_drawer = false;
+            continue;
+        }
+        let trimmed = line.trim();
+        if let Some(clock) = trimmed.strip_prefix("CLOCK:") {
+            let name = match path.last() {
+                Some(name) => name.to_owned(),
+                None => continue,
+            };
+            let clock = parse_clock(clock).map_err(|error| {
+                TrackerError::InvalidValue(format!("line {}: {}", number + 1, error))
+            })?;
+            if let Some((start, end)) = clock {
+                let project = path
+                    .len()
+                    .checked_sub(2)
+                    .map(|parent| path[parent].to_owned())
+                    .filter(|project| !project.is_empty());
+                entries.push(ImportEntry {
+                    name,
+                    project,
+                    tags: tags.to_owned(),
+                    notes: None,
+                    start,
+                    end,
+                    precision: 60,
+                });
+            }
+        } else if trimmed == ":END:" {
+            in_drawer = false;
+        } else if trimmed.starts_with(':') && trimmed.ends_with(':') && trimmed.len() > 1 {
+            in_drawer = true;
+        } else if !in_drawer && !path.is_empty() && !trimmed.is_empty() && !trimmed.starts_with('#')
+        {
+            notes.push(trimmed.to_string());
+        }
+    }
+    finish_notes(&mut entries[first_entry..], &mut notes);
+    Ok(entries)
+}
+
+/// gives the notes read under a heading to its entries.
+fn finish_notes(entries: &mut [ImportEntry], notes: &mut Vec<String>) {
+    if !notes.is_empty() {
+        for entry in entries.iter_mut() {
+            entry.notes = Some(notes.join("\n"));
+        }
+    }
+    notes.clear();
+}
+
+/// the title without a TODO keyword and the tags.
+fn heading(text: &str) -> (String, Vec<String>) {
+    let mut title = text.trim();
+    let mut tags = Vec::new();
+    if let Some(last) = title.split_whitespace().last() {
+        if last.len() > 2 && last.starts_with(':') && last.ends_with(':') {
+            tags = last
+                .trim_matches(':')
+                .split(':')
+                .filter(|tag| !tag.is_empty())
+                .map(String::from)
+                .collect();
+            title = title[..title.len() - last.len()].trim_end();
+        }
+    }
+    for keyword in ["TODO ", "DONE "] {
+        title = title.strip_prefix(keyword).unwrap_or(title);
+    }
+    (title.to_string(), tags)
+}
+
+/// None for a clock that is still running.
+fn parse_clock(text: &str) -> Result<Option<(SecType, SecType)>, TrackerError> {
+    let text = text.split("=>").next().unwrap_or_default().trim();
+    match text.split_once("--") {
+        Some((start, end)) => Ok(Some((parse_timestamp(start)?, parse_timestamp(end)?))),
+        None => {
+            parse_timestamp(text)?;
+            Ok(None)
+        }
+    }
+}
+
+/// reads `[2026-10-18 Sun 09:00]`, the weekday is not checked.
+fn parse_timestamp(text: &str) -> Result<SecType, TrackerError> {
+    let error = || TrackerError::InvalidTime(format!("can't read timestamp: {}", text));
+    let inner = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .ok_or_else(error)?;
+    let parts: Vec<&str> = inner.split_whitespace().collect();
+    let (date, time) = match parts[..] {
+        [date, _, time] | [date, time] => (date, time),
+        _ => return Err(error()),
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| error())?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| error())?;
+    local_to_secs(&NaiveDateTime::new(date, time), inner)
+}
+
+fn timestamp(secs: SecType) -> String {
+    format!(
+        "[{}]",
+        Local
+            .timestamp_opt(secs as i64, 0)
+            .unwrap()
+            .format(TIMESTAMP_FORMAT)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::parse_at;
+    use crate::structs::import::import;
+
+    #[test]
+    fn reading_clocks() {
+        let text = "#+TITLE: work\n\
+            * Billing\n\
+            ** TODO Review :backend:urgent:\n\
+            \x20  :LOGBOOK:\n\
+            \x20  CLOCK: [2026-10-18 Sun 11:00]\n\
+            \x20  CLOCK: [2026-10-18 Sun 09:00]--[2026-10-18 Sun 10:30] =>  1:30\n\
+            \x20  :END:\n\
+            \x20  first line\n\
+            \x20  second line\n\
+            * Mail\n\
+            CLOCK: [2026-10-17 Sat 08:00]--[2026-10-17 Sat 08:20] =>  0:20\n";
+        let entries = parse(text).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "Review");
+        assert_eq!(entries[0].project, Some("Billing".to_string()));
+        assert_eq!(entries[0].tags, ["backend", "urgent"]);
+        assert_eq!(
+            entries[0].notes,
+            Some("first line\nsecond line".to_string())
+        );
+        assert_eq!(entries[0].start, parse_at("2026-10-18 09:00", 0).unwrap());
+        assert_eq!(entries[0].end - entries[0].start, 90 * 60);
+        assert_eq!(entries[1].name, "Mail");
+        assert_eq!(entries[1].project, None);
+        assert_eq!(entries[1].notes, None);
+
+        assert!(parse("* a\nCLOCK: [yesterday]--[today]\n").is_err());
+    }
+
+    #[test]
+    fn export_reads_back_the_same() {
+        let nine = parse_at("2026-10-18 09:00", 0).unwrap();
+        let mut review = Activity::new("review");
+        review.set_project("billing");
+        review.set_tags(&["back end".to_string()]);
+        review.set_notes("checked the login");
+        review.stop_at(0);
+        review.start_at(nine);
+        review.stop_at(nine + 3600 + 59);
+        review.start_at(nine + 7200);
+        review.stop_at(nine + 7200 + 600);
+        let mut mail = Activity::new("mail");
+        mail.stop_at(0);
+        mail.start_at(nine + 3600 + 59);
+
+        let text = export(&[mail, review.clone()]);
+        assert_eq!(
+            text,
+            "* billing\n\
+            ** review :back_end:\n\
+            \x20  :LOGBOOK:\n\
+            \x20  CLOCK: [2026-10-18 Sun 11:00]--[2026-10-18 Sun 11:10] =>  0:10\n\
+            \x20  CLOCK: [2026-10-18 Sun 09:00]--[2026-10-18 Sun 10:00] =>  1:00\n\
+            \x20  :END:\n\
+            \x20  checked the login\n\
+            * mail\n\
+            \x20  :LOGBOOK:\n\
+            \x20  CLOCK: [2026-10-18 Sun 10:00]\n\
+            \x20  :END:\n"
+        );
+        let entries = parse(&text).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].name, "review");
+        assert_eq!(entries[1].project, Some("billing".to_string()));
+        assert_eq!(entries[1].tags, ["back_end"]);
+        assert_eq!(entries[1].notes, Some("checked the login".to_string()));
+        assert_eq!(entries[1].start, nine);
+
+        let mut tasks = vec![review];
+        let summary = import(&mut tasks, &entries, nine + 86400);
+        assert_eq!(summary.duplicates.len(), 2);
+        assert!(summary.skipped.is_empty());
+    }
+}
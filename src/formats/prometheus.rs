@@ -0,0 +1,167 @@
+use crate::structs::metrics::HttpMetrics;
+use crate::structs::report::Report;
+use crate::structs::task::*;
+
+/// The tracked time and the http requests in the prometheus text format.
+/// `today` is the report of the current day.
+pub fn render(tasks: &[Activity], today: &Report, http: &HttpMetrics) -> String {
+    let mut result = String::new();
+
+    header(
+        &mut result,
+        "tracker_activity_seconds_total",
+        "counter",
+        "Tracked time of an activity since it was created.",
+    );
+    for task in tasks {
+        result.push_str(&format!(
+            "tracker_activity_seconds_total{{activity=\"{}\",project=\"{}\"}} {}\n",
+            label(&task.name()),
+            label(&task.project().unwrap_or_default()),
+            task.secs_since_creation()
+        ));
+    }
+
+    header(
+        &mut result,
+        "tracker_activity_active",
+        "gauge",
+        "1 if the activity is running, 0 otherwise.",
+    );
+    for task in tasks {
+        result.push_str(&format!(
+            "tracker_activity_active{{activity=\"{}\"}} {}\n",
+            label(&task.name()),
+            u8::from(task.is_active())
+        ));
+    }
+
+    header(
+        &mut result,
+        "tracker_active_activities",
+        "gauge",
+        "Number of running activities.",
+    );
+    result.push_str(&format!(
+        "tracker_active_activities {}\n",
+        tasks.iter().filter(|task| task.is_active()).count()
+    ));
+
+    for (name, help, value) in [
+        (
+            "tracker_today_seconds",
+            "Time anything was running today.",
+            today.wall_clock_secs as i64,
+        ),
+        (
+            "tracker_today_target_seconds",
+            "Expected working time today.",
+            today.target_secs as i64,
+        ),
+        (
+            "tracker_today_difference_seconds",
+            "Time today minus the target, negative while under it.",
+            today.difference_secs,
+        ),
+    ] {
+        header(&mut result, name, "gauge", help);
+        result.push_str(&format!("{} {}\n", name, value));
+    }
+
+    header(
+        &mut result,
+        "tracker_http_requests_total",
+        "counter",
+        "Answered http requests.",
+    );
+    for ((method, route, status), count) in http.requests.iter() {
+        result.push_str(&format!(
+            "tracker_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}\n",
+            label(method),
+            label(route),
+            status,
+            count
+        ));
+    }
+
+    header(
+        &mut result,
+        "tracker_http_request_duration_seconds",
+        "histogram",
+        "Time taken to answer http requests.",
+    );
+    for ((method, route), histogram) in http.durations.iter() {
+        let labels = format!("method=\"{}\",route=\"{}\"", label(method), label(route));
+        for (bound, count) in histogram.cumulative() {
+            result.push_str(&format!(
+                "tracker_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}\n",
+                labels, bound, count
+            ));
+        }
+        result.push_str(&format!(
+            "tracker_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}\n",
+            labels, histogram.count
+        ));
+        result.push_str(&format!(
+            "tracker_http_request_duration_seconds_sum{{{}}} {}\n",
+            labels, histogram.sum
+        ));
+        result.push_str(&format!(
+            "tracker_http_request_duration_seconds_count{{{}}} {}\n",
+            labels, histogram.count
+        ));
+    }
+    result
+}
+
+fn header(result: &mut String, name: &str, kind: &str, help: &str) {
+    result.push_str(&format!("# HELP {} {}\n", name, help));
+    result.push_str(&format!("# TYPE {} {}\n", name, kind));
+}
+
+/// escapes a label value.
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::parse_date;
+    use crate::structs::rounding::Rounding;
+
+    #[test]
+    fn metrics_text() {
+        let mut alpha = Activity::new("say \"hi\"");
+        alpha.set_project("billing");
+        alpha.stop_at(0);
+        alpha.start_at(100);
+        alpha.stop_at(160);
+        let mut beta = Activity::new("beta");
+        beta.start_at(sys_now_secs());
+        let tasks = vec![alpha, beta];
+        let date = parse_date("2026-10-12").unwrap();
+        let today = Report::new(&tasks, date, date, &Rounding::default(), 3600).unwrap();
+        let mut http = HttpMetrics::default();
+        http.record("POST", "/api/start/{name}", 200, 0.001);
+
+        let text = render(&tasks, &today, &http);
+        assert!(text.contains("# TYPE tracker_activity_seconds_total counter\n"));
+        assert!(text.contains(
+            "tracker_activity_seconds_total{activity=\"say \\\"hi\\\"\",project=\"billing\"} 60\n"
+        ));
+        assert!(text.contains("tracker_activity_active{activity=\"beta\"} 1\n"));
+        assert!(text.contains("tracker_active_activities 1\n"));
+        assert!(text.contains("tracker_today_target_seconds 3600\n"));
+        assert!(text.contains("tracker_today_difference_seconds -3600\n"));
+        assert!(text.contains(
+            "tracker_http_requests_total{method=\"POST\",route=\"/api/start/{name}\",status=\"200\"} 1\n"
+        ));
+        assert!(text.contains(
+            "tracker_http_request_duration_seconds_bucket{method=\"POST\",route=\"/api/start/{name}\",le=\"+Inf\"} 1\n"
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::structs::dates::{check_range, parse_date};
+use crate::structs::error::TrackerError;
+use crate::structs::report::{Period, Report};
+use crate::structs::task::*;
+use chrono::{Duration, NaiveDate};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Style {
+    /// a heading per day and a bullet per activity.
+    Markdown,
+    /// the layout of the pretty endpoint.
+    Text,
+}
+
+/// Settings of a standup summary.
+#[derive(Clone, PartialEq, Debug)]
+pub struct StandupOptions {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub style: Style,
+}
+
+impl StandupOptions {
+    /// `range` is yesterday, today, week, lastweek or a YYYY-MM-DD date,
+    /// yesterday by default. `from` and `to` override it.
+    pub fn parse(
+        range: Option<&str>,
+        from: Option<&str>,
+        to: Option<&str>,
+        format: Option<&str>,
+        today: NaiveDate,
+    ) -> Result<Self, TrackerError> {
+        let (mut first, mut last) = match range.unwrap_or("yesterday").trim() {
+            "yesterday" => {
+                let yesterday = today - Duration::days(1);
+                (yesterday, yesterday)
+            }
+            "today" => (today, today),
+            "week" => Period::Week.range(today),
+            "lastweek" => Period::Week.range(today - Duration::days(7)),
+            date => {
+                let date = parse_date(date).map_err(|_| {
+                    TrackerError::InvalidValue(format!(
+                        "range can be yesterday, today, week, lastweek or YYYY-MM-DD, got: {}",
+                        date
+                    ))
+                })?;
+                (date, date)
+            }
+        };
+        if let Some(from) = from {
+            first = parse_date(from)?;
+        }
+        if let Some(to) = to {
+            last = parse_date(to)?;
+        }
+        check_range(first, last)?;
+        let style = match format.unwrap_or("markdown") {
+            "markdown" | "md" => Style::Markdown,
+            "text" => Style::Text,
+            other => {
+                return Err(TrackerError::InvalidValue(format!(
+                    "format can be markdown or text, got: {}",
+                    other
+                )))
+            }
+        };
+        Ok(Self {
+            from: first,
+            to: last,
+            style,
+        })
+    }
+}
+
+/// The activities of every day in the report that has tracked time,
+/// with their notes. The notes come from `tasks`.
+pub fn render(report: &Report, tasks: &[Activity], style: Style) -> String {
+    let notes = |name: &str| {
+        tasks
+            .iter()
+            .find(|task| task.name() == name)
+            .and_then(|task| task.notes())
+    };
+    let mut result = String::new();
+    for day in report.days.iter().filter(|day| day.total_secs > 0) {
+        let mut names: Vec<(&String, &SecType)> = day.activities.iter().collect();
+        names.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        match style {
+            Style::Markdown => {
+                result.push_str(&format!("## {} {}\n\n", day.weekday, day.date));
+                for (name, secs) in names {
+                    result.push_str(&format!("- **{}** {}", name, secs_to_pretty(*secs)));
+                    if let Some(notes) = notes(name) {
+                        result.push_str(&format!(": {}", notes.replace('\n', " ")));
+                    }
+                    result.push('\n');
+                }
+                result.push('\n');
+            }
+            Style::Text => {
+                result.push_str(&format!("{} {}:\n", day.weekday, day.date));
+                for (name, secs) in names {
+                    result.push_str(&format!("  {}\n", name_time_text(name, *secs)));
+                    if let Some(notes) = notes(name) {
+                        for line in notes.lines() {
+                            result.push_str(&format!("  {:>45}  {}\n", "", line));
+                        }
+                    }
+                }
+                result.push('\n');
+            }
+        }
+    }
+    if result.is_empty() {
+        result.push_str(&format!(
+            "nothing tracked between {} and {}.\n",
+            report.from, report.to
+        ));
+        return result;
+    }
+    match style {
+        Style::Markdown => result.push_str(&format!("**total:** {}\n", report.total_pretty)),
+        Style::Text => result.push_str(&format!("total acivity time: {}\n", report.total_pretty)),
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::day_start;
+    use crate::structs::rounding::Rounding;
+
+    fn date(text: &str) -> NaiveDate {
+        parse_date(text).unwrap()
+    }
+
+    #[test]
+    fn parsing_options() {
+        let today = date("2026-10-15");
+        let options = StandupOptions::parse(None, None, None, None, today).unwrap();
+        assert_eq!(
+            (options.from, options.to),
+            (date("2026-10-14"), date("2026-10-14"))
+        );
+        assert_eq!(options.style, Style::Markdown);
+        let options = StandupOptions::parse(Some("lastweek"), None, None, Some("text"), today);
+        let options = options.unwrap();
+        assert_eq!(
+            (options.from, options.to),
+            (date("2026-10-05"), date("2026-10-11"))
+        );
+        assert_eq!(options.style, Style::Text);
+        let options = StandupOptions::parse(None, Some("2026-10-01"), None, None, today).unwrap();
+        assert_eq!(options.from, date("2026-10-01"));
+        assert!(StandupOptions::parse(Some("tomorrow"), None, None, None, today).is_err());
+        assert!(StandupOptions::parse(None, None, None, Some("html"), today).is_err());
+        assert!(
+            StandupOptions::parse(Some("today"), None, Some("2026-10-01"), None, today).is_err()
+        );
+    }
+
+    #[test]
+    fn markdown_and_text() {
+        let day = date("2026-10-14");
+        let nine = day_start(day) + 9 * 60 * 60;
+        let mut alpha = Activity::new("alpha");
+        alpha.set_notes("fixed the login");
+        alpha.stop_at(0);
+        alpha.start_at(nine);
+        alpha.stop_at(nine + 90 * 60);
+        let mut beta = Activity::new("beta");
+        beta.stop_at(0);
+        beta.start_at(nine + 90 * 60);
+        beta.stop_at(nine + 120 * 60);
+        let tasks = vec![beta, alpha];
+        let report = Report::new(&tasks, day, day, &Rounding::default(), 0).unwrap();
+
+        assert_eq!(
+            render(&report, &tasks, Style::Markdown),
+            "## Wed 2026-10-14\n\n\
+            - **alpha** 01h:30m: fixed the login\n\
+            - **beta** 00h:30m\n\n\
+            **total:** 02h:00m\n"
+        );
+        let text = render(&report, &tasks, Style::Text);
+        assert!(text.starts_with("Wed 2026-10-14:\n"));
+        assert!(text.contains(&name_time_text("alpha", 90 * 60)));
+        assert!(text.contains("  fixed the login\n"));
+        assert!(text.ends_with("total acivity time: 02h:00m\n"));
+
+        let empty = Report::new(
+            &tasks,
+            date("2026-10-20"),
+            date("2026-10-20"),
+            &Rounding::default(),
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            render(&empty, &tasks, Style::Text),
+            "nothing tracked between 2026-10-20 and 2026-10-20.\n"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! The timeclock format of ledger and hledger: an `i` line when
+//! an activity starts and an `o` line when it stops, in local time.
+//!
+//!     i 2026/10/18 09:00:00 billing:review
+//!     o 2026/10/18 10:00:00
+//!
+//! The account is `project:activity`, or the activity without a project.
+//! ledger splits accounts at `:`, so a `:` in a project or an activity
+//! is written as `%3A` and a `%` as `%25`.
+
+use crate::structs::dates::local_to_secs;
+use crate::structs::error::TrackerError;
+use crate::structs::import::ImportEntry;
+use crate::structs::task::*;
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+const DATE_FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+
+/// Every interval as an `i` and `o` pair, oldest first. A running
+/// activity is clocked in without a clock out, ledger counts it
+/// up to now.
+pub fn export(tasks: &[Activity]) -> String {
+    let mut pairs: Vec<(SecType, String, Option<SecType>)> = Vec::new();
+    for task in tasks {
+        let account = match task.project() {
+            Some(project) => format!("{}:{}", escape(&project), escape(&task.name())),
+            None => escape(&task.name()),
+        };
+        let intervals = task.intervals();
+        for (index, interval) in intervals.iter().enumerate() {
+            let running = task.is_active() && index == intervals.len() - 1;
+            let end = if running { None } else { Some(interval.end) };
+            pairs.push((interval.start, account.to_owned(), end));
+        }
+    }
+    pairs.sort();
+
+    let mut result = String::new();
+    for (start, account, end) in pairs {
+        result.push_str(&format!("i {} {}\n", local_time(start), account));
+        if let Some(end) = end {
+            result.push_str(&format!("o {}\n", local_time(end)));
+        }
+    }
+    result
+}
+
+/// The clocked intervals of a timeclock file. An `i` without an `o`
+/// is still running, it is left out.
+pub fn parse(text: &str) -> Result<Vec<ImportEntry>, TrackerError> {
+    let mut entries = Vec::new();
+    let mut clocked_in: Option<(SecType, String)> = None;
+    for (number, line) in text.lines().enumerate() {
+        let line_error = |error: TrackerError| {
+            TrackerError::InvalidValue(format!("line {}: {}", number + 1, error))
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with([';', '#', '*']) {
+            continue;
+        }
+        let mut parts = line.splitn(4, ' ');
+        let code = parts.next().unwrap_or_default();
+        let date = parts.next().unwrap_or_default();
+        let time = parts.next().unwrap_or_default();
+        let rest = parts.next().unwrap_or_default().trim();
+        let at = parse_time(date, time).map_err(line_error)?;
+        match (code, clocked_in.take()) {
+            ("i", None) => {
+                // a description follows the account after two spaces.
+                let account = rest.split("  ").next().unwrap_or_default().trim();
+                if account.is_empty() {
+                    return Err(line_error(TrackerError::InvalidValue(
+                        "clock in without an account".to_string(),
+                    )));
+                }
+                clocked_in = Some((at, account.to_string()));
+            }
+            ("o" | "O", Some((start, account))) => {
+                let (project, name) = match account.split_once(':') {
+                    Some((project, name)) => (Some(unescape(project)), unescape(name)),
+                    None => (None, unescape(&account)),
+                };
+                entries.push(ImportEntry {
+                    name,
+                    project,
+                    tags: Vec::new(),
+                    notes: None,
+                    start,
+                    end: at,
+                    precision: 1,
+                });
+            }
+            ("i", Some(_)) => {
+                return Err(line_error(TrackerError::InvalidValue(
+                    "clock in while clocked in".to_string(),
+                )))
+            }
+            ("o" | "O", None) => {
+                return Err(line_error(TrackerError::InvalidValue(
+                    "clock out without a clock in".to_string(),
+                )))
+            }
+            (code, _) => {
+                return Err(line_error(TrackerError::InvalidValue(format!(
+                    "unknown code: {}",
+                    code
+                ))))
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_time(date: &str, time: &str) -> Result<SecType, TrackerError> {
+    let parsed_date = ["%Y/%m/%d", "%Y-%m-%d", "%Y.%m.%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| TrackerError::InvalidTime(format!("can't read date: {}", date)))?;
+    let parsed_time = ["%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(time, format).ok())
+        .ok_or_else(|| TrackerError::InvalidTime(format!("can't read time: {}", time)))?;
+    local_to_secs(
+        &NaiveDateTime::new(parsed_date, parsed_time),
+        &format!("{} {}", date, time),
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('%', "%25").replace(':', "%3A")
+}
+
+/// undoes `escape`, a `%` that isn't part of an escape is kept.
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(position) = rest.find('%') {
+        result.push_str(&rest[..position]);
+        rest = &rest[position..];
+        if let Some(after) = rest.strip_prefix("%3A") {
+            result.push(':');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("%25") {
+            result.push('%');
+            rest = after;
+        } else {
+            result.push('%');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn local_time(secs: SecType) -> String {
+    Local
+        .timestamp_opt(secs as i64, 0)
+        .unwrap()
+        .format(DATE_FORMAT)
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::parse_at;
+
+    #[test]
+    fn reading_clock_lines() {
+        let text = "; exported from an other tool\n\
+            i 2026/10/18 09:00:00 billing:code review  fixing the login\n\
+            o 2026/10/18 10:30:00\n\
+            \n\
+            i 2026-10-18 11:00 mail\n\
+            o 2026-10-18 11:15\n\
+            i 2026/10/18 12:00:00 lunch\n";
+        let entries = parse(text).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "code review");
+        assert_eq!(entries[0].project, Some("billing".to_string()));
+        assert_eq!(entries[0].start, parse_at("2026-10-18 09:00", 0).unwrap());
+        assert_eq!(entries[0].end - entries[0].start, 90 * 60);
+        assert_eq!(entries[1].name, "mail");
+        assert_eq!(entries[1].project, None);
+
+        assert!(parse("o 2026/10/18 10:30:00\n").is_err());
+        assert!(parse("i 2026/10/18 09:00:00 a\ni 2026/10/18 10:00:00 b\n").is_err());
+        assert!(parse("i 18.10.2026 09:00:00 a\n").is_err());
+        assert!(parse("x 2026/10/18 09:00:00 a\n").is_err());
+    }
+
+    #[test]
+    fn export_reads_back_the_same() {
+        let nine = parse_at("2026-10-18 09:00", 0).unwrap();
+        let mut review = Activity::new("review");
+        review.set_project("billing");
+        review.stop_at(0);
+        review.start_at(nine);
+        review.stop_at(nine + 3600);
+        let mut mail = Activity::new("mail");
+        mail.stop_at(0);
+        mail.start_at(nine + 3600);
+        mail.stop_at(nine + 4500);
+        let mut running = Activity::new("lunch");
+        running.stop_at(0);
+        running.start_at(nine + 7200);
+
+        let text = export(&[mail, review, running]);
+        assert_eq!(
+            text,
+            "i 2026/10/18 09:00:00 billing:review\n\
+            o 2026/10/18 10:00:00\n\
+            i 2026/10/18 10:00:00 mail\n\
+            o 2026/10/18 10:15:00\n\
+            i 2026/10/18 11:00:00 lunch\n"
+        );
+        let entries = parse(&text).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].project, Some("billing".to_string()));
+        assert_eq!(entries[0].start, nine);
+        assert_eq!(entries[1].end, nine + 4500);
+    }
+
+    #[test]
+    fn colons_in_names() {
+        let nine = parse_at("2026-10-18 09:00", 0).unwrap();
+        let mut task = Activity::new("review: 100%3A");
+        task.set_project("acme:web");
+        task.stop_at(0);
+        task.start_at(nine);
+        task.stop_at(nine + 3600);
+
+        let text = export(&[task]);
+        assert!(text.starts_with("i 2026/10/18 09:00:00 acme%3Aweb:review%3A 100%253A\n"));
+        let entries = parse(&text).unwrap();
+        assert_eq!(entries[0].name, "review: 100%3A");
+        assert_eq!(entries[0].project, Some("acme:web".to_string()));
+        assert_eq!(unescape("50% off"), "50% off");
+    }
+}
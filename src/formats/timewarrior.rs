@@ -0,0 +1,346 @@
+//! Timewarrior's data files, one `YYYY-MM.data` file per month with
+//! lines like `inc 20261014T090000Z - 20261014T100000Z # review "big tag"`.
+//! An `activity:<name>` tag is the activity, a `project:<name>` tag the
+//! project and the other tags are the activity's tags. The annotation
+//! holds the notes.
+
+use crate::structs::error::TrackerError;
+use crate::structs::import::{ImportEntry, UNNAMED};
+use crate::structs::task::*;
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use std::collections::BTreeMap;
+
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const PROJECT_TAG: &str = "project:";
+const ACTIVITY_TAG: &str = "activity:";
+
+/// The intervals by data file name. A running activity gets an open
+/// interval, like `timew start` writes it.
+pub fn export(tasks: &[Activity]) -> BTreeMap<String, String> {
+    let mut lines: Vec<(SecType, String)> = Vec::new();
+    for task in tasks {
+        let mut tags = vec![format!("{}{}", ACTIVITY_TAG, task.name())];
+        if let Some(project) = task.project() {
+            tags.push(format!("{}{}", PROJECT_TAG, project));
+        }
+        tags.extend(task.tags());
+        let mut suffix = format!(
+            " # {}",
+            tags.iter().map(|t| quote(t)).collect::<Vec<_>>().join(" ")
+        );
+        if let Some(notes) = task.notes() {
+            suffix.push_str(&format!(" # {}", quote(&notes)));
+        }
+        let intervals = task.intervals();
+        for (index, interval) in intervals.iter().enumerate() {
+            let running = task.is_active() && index == intervals.len() - 1;
+            let line = if running {
+                format!("inc {}{}", utc_time(interval.start), suffix)
+            } else {
+                format!(
+                    "inc {} - {}{}",
+                    utc_time(interval.start),
+                    utc_time(interval.end),
+                    suffix
+                )
+            };
+            lines.push((interval.start, line));
+        }
+    }
+    lines.sort();
+
+    let mut files: BTreeMap<String, String> = BTreeMap::new();
+    for (start, line) in lines {
+        let name = Utc
+            .timestamp_opt(start as i64, 0)
+            .unwrap()
+            .format("%Y-%m.data")
+            .to_string();
+        let file = files.entry(name).or_default();
+        file.push_str(&line);
+        file.push('\n');
+    }
+    files
+}
+
+/// The closed intervals of data files. Open intervals are still
+/// running in Timewarrior, they are left out. `known` are the names
+/// of the stored activities, see `activity_name`.
+pub fn parse(text: &str, known: &[String]) -> Result<Vec<ImportEntry>, TrackerError> {
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let entry = parse_line(line, known).map_err(|error| {
+            TrackerError::InvalidValue(format!("line {}: {}", number + 1, error))
+        })?;
+        entries.extend(entry);
+    }
+    Ok(entries)
+}
+
+/// the data files of a directory, in order, as one text.
+pub fn read_dir(path: &std::path::Path) -> std::io::Result<String> {
+    let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "data")
+        })
+        .filter(|path| path.file_name().is_some_and(|name| name != "tags.data"))
+        .collect();
+    paths.sort();
+    let mut text = String::new();
+    for path in paths {
+        text.push_str(&std::fs::read_to_string(path)?);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+fn parse_line(line: &str, known: &[String]) -> Result<Option<ImportEntry>, TrackerError> {
+    let rest = line
+        .strip_prefix("inc ")
+        .ok_or_else(|| TrackerError::InvalidValue(format!("not an interval: {}", line)))?;
+    let (times, labels) = match rest.find(" #") {
+        Some(position) => (&rest[..position], &rest[position + 2..]),
+        None => (rest, ""),
+    };
+    let times: Vec<&str> = times.split_whitespace().collect();
+    let (start, end) = match times[..] {
+        [start] => (parse_time(start)?, None),
+        [start, "-", end] => (parse_time(start)?, Some(parse_time(end)?)),
+        _ => {
+            return Err(TrackerError::InvalidTime(format!(
+                "can't read the times: {}",
+                times.join(" ")
+            )))
+        }
+    };
+    let end = match end {
+        Some(end) => end,
+        None => return Ok(None),
+    };
+
+    let tokens = tokenize(labels)?;
+    let mut sections = tokens.split(|(token, quoted)| !quoted && token == "#");
+    let mut tags: Vec<String> = sections
+        .next()
+        .unwrap_or_default()
+        .iter()
+        .map(|(token, _)| token.to_owned())
+        .collect();
+    let notes = sections
+        .next()
+        .map(|annotation| {
+            annotation
+                .iter()
+                .map(|(token, _)| token.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|notes| !notes.is_empty());
+    let name = activity_name(&mut tags, known);
+    let project = tags
+        .iter()
+        .find_map(|tag| tag.strip_prefix(PROJECT_TAG))
+        .map(String::from);
+    tags.retain(|tag| !tag.starts_with(PROJECT_TAG));
+    Ok(Some(ImportEntry {
+        name,
+        project,
+        tags,
+        notes,
+        start,
+        end,
+        precision: 1,
+    }))
+}
+
+/// Timewarrior sorts the tags of an interval, so their order doesn't
+/// tell which one is the activity. An `activity:<name>` tag like the
+/// export writes names it, then a tag that is the name of a stored
+/// activity, then the only tag there is. Otherwise the interval goes
+/// to the imported activity and keeps all its tags. The tag taken as
+/// the name is removed from the tags.
+fn activity_name(tags: &mut Vec<String>, known: &[String]) -> String {
+    if let Some(position) = tags.iter().position(|tag| tag.starts_with(ACTIVITY_TAG)) {
+        return tags.remove(position)[ACTIVITY_TAG.len()..].to_string();
+    }
+    if let Some(position) = tags.iter().position(|tag| known.contains(tag)) {
+        return tags.remove(position);
+    }
+    let plain: Vec<usize> = (0..tags.len())
+        .filter(|index| !tags[*index].starts_with(PROJECT_TAG))
+        .collect();
+    match plain[..] {
+        [index] => tags.remove(index),
+        _ => UNNAMED.to_string(),
+    }
+}
+
+fn parse_time(text: &str) -> Result<SecType, TrackerError> {
+    let naive = NaiveDateTime::parse_from_str(text, TIME_FORMAT)
+        .map_err(|_| TrackerError::InvalidTime(format!("can't read time: {}", text)))?;
+    SecType::try_from(naive.timestamp())
+        .map_err(|_| TrackerError::InvalidTime(format!("{} is before the epoch", text)))
+}
+
+fn utc_time(secs: SecType) -> String {
+    Utc.timestamp_opt(secs as i64, 0)
+        .unwrap()
+        .format(TIME_FORMAT)
+        .to_string()
+}
+
+/// quotes tags Timewarrior would split or read as a separator.
+fn quote(text: &str) -> String {
+    let plain = !text.is_empty()
+        && !text
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '#' || c == '\\');
+    if plain {
+        return text.to_string();
+    }
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+/// splits on whitespace, keeping quoted parts together. The flag tells
+/// if a token was quoted, a quoted "#" is not a separator.
+fn tokenize(text: &str) -> Result<Vec<(String, bool)>, TrackerError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c != '"' {
+            let mut token = c.to_string();
+            while let Some(next) = chars.next_if(|next| !next.is_whitespace()) {
+                token.push(next);
+            }
+            tokens.push((token, false));
+            continue;
+        }
+        let mut token = String::new();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => token.push('\n'),
+                    Some(escaped) => token.push(escaped),
+                    None => break,
+                },
+                Some(c) => token.push(c),
+                None => {
+                    return Err(TrackerError::InvalidValue(format!(
+                        "unclosed quote: {}",
+                        text
+                    )))
+                }
+            }
+        }
+        tokens.push((token, true));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::import::import;
+
+    #[test]
+    fn reading_data_lines() {
+        let text = "inc 20261014T090000Z - 20261014T100000Z # activity:review project:billing \"big tag\"\n\
+            \n\
+            inc 20261014T100000Z - 20261014T101500Z # mail # \"answered \\\"all\\\"\"\n\
+            inc 20261014T110000Z - 20261014T113000Z\n\
+            inc 20261014T120000Z # review\n";
+        let entries = parse(text, &[]).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "review");
+        assert_eq!(entries[0].project, Some("billing".to_string()));
+        assert_eq!(entries[0].tags, ["big tag"]);
+        assert_eq!(entries[0].end - entries[0].start, 3600);
+        assert_eq!(entries[0].start, parse_time("20261014T090000Z").unwrap());
+        assert_eq!(entries[1].notes, Some("answered \"all\"".to_string()));
+        assert_eq!(entries[2].name, UNNAMED);
+
+        assert!(parse("inc 2026-10-14 - 20261014T100000Z # x", &[]).is_err());
+        assert!(parse("exc 20261014T090000Z", &[]).is_err());
+        assert!(parse("inc 20261014T090000Z - 20261014T100000Z # \"open", &[]).is_err());
+    }
+
+    #[test]
+    fn naming_sorted_tags() {
+        // Timewarrior writes the tags of `timew start review backend`
+        // sorted.
+        let text = "inc 20261014T090000Z - 20261014T100000Z # backend project:billing review\n";
+        let entries = parse(text, &["review".to_string()]).unwrap();
+        assert_eq!(entries[0].name, "review");
+        assert_eq!(entries[0].tags, ["backend"]);
+        assert_eq!(entries[0].project, Some("billing".to_string()));
+
+        let entries = parse(text, &[]).unwrap();
+        assert_eq!(entries[0].name, UNNAMED);
+        assert_eq!(entries[0].tags, ["backend", "review"]);
+
+        let text =
+            "inc 20261014T090000Z - 20261014T100000Z # \"activity:code review\" backend review\n";
+        let entries = parse(text, &["review".to_string()]).unwrap();
+        assert_eq!(entries[0].name, "code review");
+        assert_eq!(entries[0].tags, ["backend", "review"]);
+    }
+
+    #[test]
+    fn export_reads_back_the_same() {
+        let start = parse_time("20260814T090000Z").unwrap();
+        let mut review = Activity::new("code review");
+        review.set_project("billing");
+        review.set_tags(&["#1".to_string(), "backend".to_string()]);
+        review.set_notes("line one\nline two");
+        review.stop_at(0);
+        review.start_at(start);
+        review.stop_at(start + 3600);
+        review.start_at(start + 40 * 24 * 3600);
+        review.stop_at(start + 40 * 24 * 3600 + 600);
+        let mut running = Activity::new("mail");
+        running.stop_at(0);
+        running.start_at(start + 7200);
+
+        let files = export(&[review.clone(), running]);
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["2026-08.data", "2026-09.data"]
+        );
+        let august = &files["2026-08.data"];
+        assert!(august.starts_with(
+            "inc 20260814T090000Z - 20260814T100000Z # \"activity:code review\" project:billing \"#1\" backend # \"line one\\nline two\"\n"
+        ));
+        assert!(august.ends_with("inc 20260814T110000Z # activity:mail\n"));
+
+        // Timewarrior keeps the tags sorted.
+        let sorted: String = files.values().cloned().collect::<String>().replace(
+            "\"activity:code review\" project:billing \"#1\" backend",
+            "\"#1\" \"activity:code review\" backend project:billing",
+        );
+        assert!(sorted.contains("\"#1\" \"activity:code review\""));
+        let entries = parse(&sorted, &[]).unwrap();
+        assert_eq!(entries.len(), 2);
+        let mut tasks = Vec::new();
+        let summary = import(&mut tasks, &entries, start + 41 * 24 * 3600);
+        assert_eq!(summary.intervals, 2);
+        assert_eq!(tasks[0].name(), review.name());
+        assert_eq!(tasks[0].project(), review.project());
+        assert_eq!(tasks[0].tags(), review.tags());
+        assert_eq!(tasks[0].notes(), review.notes());
+        assert_eq!(tasks[0].intervals(), review.intervals());
+    }
+}
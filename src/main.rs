@@ -1,36 +1,230 @@
 mod api_views;
 mod client_views;
 mod config;
+mod formats;
 mod structs;
 
+use actix_web::dev::Service;
 use actix_web::web::{self, Data};
 use actix_web::{App, HttpServer};
+use api_views::resources;
 use api_views::views::*;
 use client_views::index::*;
+use client_views::pages::*;
+use client_views::timesheet::*;
+use config::Command;
+use std::io::Write;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use structs::metrics::HttpMetrics;
 use structs::taskmanager::TaskManager;
 
+/// how often pomodoros and schedules are checked in the background.
+const TICK_SECS: u64 = 5;
+/// largest request body, csv exports of a few years fit.
+const PAYLOAD_LIMIT_BYTES: usize = 32 * 1024 * 1024;
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     let config = config::get_congig();
-    println!("web: http://{}:{}/", config.url, config.port);
-    println!("api: http://{}:{}/api/times", config.url, config.port);
+    let mut taskmanager = TaskManager::new(config.dbpath);
+    taskmanager.set_rounding(config.rounding);
+    taskmanager.set_concurrent_mode(config.concurrent);
+    taskmanager.set_snapshots(config.snapshots);
+
+    match config.command {
+        Command::Serve => serve(config.url, config.port, taskmanager).await,
+        Command::ExportCsv(options) => {
+            print!(
+                "{}",
+                formats::csv::export(&taskmanager.activities(), &options, &taskmanager.rounding())
+            );
+            Ok(())
+        }
+        Command::Import {
+            path,
+            format,
+            dry_run,
+        } => {
+            let text = if path.is_dir() {
+                formats::timewarrior::read_dir(&path)?
+            } else {
+                std::fs::read_to_string(&path)?
+            };
+            let known: Vec<String> = taskmanager.activities().iter().map(|t| t.name()).collect();
+            match formats::import_entries(&text, Some(&format), &known) {
+                Ok(file) => {
+                    print!("{}", taskmanager.import(&file, dry_run).text());
+                    Ok(())
+                }
+                Err(error) => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    error.to_string(),
+                )),
+            }
+        }
+        Command::ExportTimeclock => {
+            print!("{}", formats::timeclock::export(&taskmanager.activities()));
+            Ok(())
+        }
+        Command::ExportOrg => {
+            print!("{}", formats::org::export(&taskmanager.activities()));
+            Ok(())
+        }
+        Command::Snapshots { restore: None } => {
+            for snapshot in taskmanager.snapshot_list() {
+                println!("{}", snapshot.text());
+            }
+            Ok(())
+        }
+        Command::Snapshots {
+            restore: Some(name),
+        } => match taskmanager.restore_snapshot(&name) {
+            Ok((snapshot, backup_path)) => {
+                println!(
+                    "restored {}, the previous state is in {}",
+                    snapshot.text(),
+                    backup_path.display()
+                );
+                Ok(())
+            }
+            Err(error) => Err(std::io::Error::other(error.to_string())),
+        },
+        Command::ExportTimewarrior(dir) => {
+            std::fs::create_dir_all(&dir)?;
+            let files = formats::timewarrior::export(&taskmanager.activities());
+            // nothing is written when one of the files is there already.
+            if let Some(path) = files
+                .keys()
+                .map(|name| dir.join(name))
+                .find(|path| path.exists())
+            {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("{} exists, nothing was written", path.display()),
+                ));
+            }
+            for (name, lines) in files {
+                let path = dir.join(name);
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&path)?
+                    .write_all(lines.as_bytes())?;
+                println!("{}", path.display());
+            }
+            Ok(())
+        }
+        Command::Standup(options) => {
+            let summary = taskmanager
+                .report(options.from, options.to)
+                .map_err(|error| std::io::Error::other(error.to_string()))?;
+            print!(
+                "{}",
+                formats::standup::render(&summary, &taskmanager.activities(), options.style)
+            );
+            Ok(())
+        }
+    }
+}
+
+async fn serve(url: String, port: u16, taskmanager: TaskManager) -> std::io::Result<()> {
+    println!("web: http://{}:{}/", url, port);
+    println!("api: http://{}:{}/api/times", url, port);
+
+    let data = Data::new(Mutex::new(taskmanager));
+
+    let ticker = Data::clone(&data);
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(TICK_SECS));
+        loop {
+            interval.tick().await;
+            ticker.lock().unwrap().tick();
+        }
+    });
 
-    let data = Data::new(Mutex::new(TaskManager::new(config.dbpath)));
+    let http_metrics = Data::new(Mutex::new(HttpMetrics::default()));
 
     HttpServer::new(move || {
+        let recorder = Data::clone(&http_metrics);
         App::new()
             .app_data(Data::clone(&data))
+            .app_data(Data::clone(&http_metrics))
+            .app_data(web::PayloadConfig::new(PAYLOAD_LIMIT_BYTES))
+            .wrap_fn(move |req, srv| {
+                let started = Instant::now();
+                let recorder = Data::clone(&recorder);
+                let method = req.method().to_string();
+                let route = req
+                    .match_pattern()
+                    .unwrap_or_else(|| "unmatched".to_string());
+                let response = srv.call(req);
+                async move {
+                    // errors are counted with their status before they
+                    // are passed on.
+                    let response = response.await;
+                    let status = match &response {
+                        Ok(response) => response.status(),
+                        Err(error) => error.as_response_error().status_code(),
+                    };
+                    recorder.lock().unwrap().record(
+                        &method,
+                        &route,
+                        status.as_u16(),
+                        started.elapsed().as_secs_f64(),
+                    );
+                    response
+                }
+            })
             .service(index_view)
+            .service(history_view)
+            .service(timeline_view)
+            .service(metrics)
+            .service(timesheet_view)
             .service(
                 web::scope("/api")
+                    .service(
+                        web::scope("/activities")
+                            .app_data(resources::json_config())
+                            .app_data(resources::path_config())
+                            .service(resources::list_activities)
+                            .service(resources::create_activity)
+                            .service(resources::get_activity)
+                            .service(resources::update_activity)
+                            .service(resources::delete_activity)
+                            .service(resources::list_intervals)
+                            .service(resources::create_interval),
+                    )
                     .service(start)
+                    .service(start_json)
                     .service(stop)
+                    .service(stop_one)
+                    .service(resume)
+                    .service(history)
+                    .service(schedules)
+                    .service(add_schedule)
+                    .service(remove_schedule)
+                    .service(report)
+                    .service(compare_report)
+                    .service(daily_stats)
+                    .service(total_stats)
+                    .service(focus)
+                    .service(timeline)
+                    .service(add_interval)
+                    .service(import)
+                    .service(backup)
+                    .service(restore)
+                    .service(export_csv)
+                    .service(export_ics)
+                    .service(export_timewarrior)
+                    .service(export_timeclock)
+                    .service(export_org)
+                    .service(standup_report)
                     .service(times)
                     .service(pretty),
             )
     })
-    .bind((config.url, config.port))?
+    .bind((url, port))?
     .workers(4)
     .run()
     .await
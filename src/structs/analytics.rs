@@ -0,0 +1,228 @@
+use super::dates::{check_range, day_start, next_day};
+use super::error::TrackerError;
+use super::task::*;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// sessions shorter than this count as fragmented time.
+pub const SHORT_SESSION_SECS: SecType = 15 * 60;
+/// sessions at least this long count as focused time.
+pub const FOCUS_SESSION_SECS: SecType = 25 * 60;
+
+/// Uninterrupted time on one activity. Intervals of the same
+/// activity that follow each other without a gap are one session.
+#[derive(Clone, PartialEq, Debug)]
+struct Session {
+    name: String,
+    start: SecType,
+    end: SecType,
+}
+
+impl Session {
+    fn secs(&self) -> SecType {
+        self.end - self.start
+    }
+}
+
+/// How chopped up a day was. Concurrent activities run in the
+/// background, so they are left out.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct DayFocus {
+    pub date: String,
+    pub tracked_secs: SecType,
+    pub sessions: usize,
+    /// changes from one activity to an other.
+    pub context_switches: usize,
+    pub median_session_secs: SecType,
+    pub longest_session_secs: SecType,
+    /// part of the tracked time in sessions under 15 minutes, 0 - 1.
+    pub short_session_share: f64,
+    /// percent of the tracked time in sessions of 25 minutes or more.
+    pub focus_score: u8,
+}
+
+impl DayFocus {
+    pub fn new(tasks: &[Activity], date: NaiveDate) -> Result<Self, TrackerError> {
+        let start = day_start(date);
+        let end = day_start(next_day(date)?);
+        let mut intervals: Vec<(String, Interval)> = tasks
+            .iter()
+            .filter(|task| !task.is_concurrent())
+            .flat_map(|task| {
+                task.intervals_between(start, end)
+                    .into_iter()
+                    .map(move |interval| (task.name(), interval))
+            })
+            .collect();
+        intervals.sort_by_key(|(_, interval)| interval.start);
+
+        let mut sessions: Vec<Session> = Vec::new();
+        for (name, interval) in intervals {
+            match sessions.last_mut() {
+                Some(last) if last.name == name && interval.start <= last.end => {
+                    last.end = last.end.max(interval.end);
+                }
+                _ => sessions.push(Session {
+                    name,
+                    start: interval.start,
+                    end: interval.end,
+                }),
+            }
+        }
+
+        let context_switches = sessions
+            .windows(2)
+            .filter(|pair| pair[0].name != pair[1].name)
+            .count();
+        let mut lengths: Vec<SecType> = sessions.iter().map(|s| s.secs()).collect();
+        lengths.sort_unstable();
+        let tracked_secs: SecType = lengths.iter().sum();
+        let short_secs: SecType = lengths.iter().filter(|s| **s < SHORT_SESSION_SECS).sum();
+        let focus_secs: SecType = lengths.iter().filter(|s| **s >= FOCUS_SESSION_SECS).sum();
+        Ok(Self {
+            date: date.format("%Y-%m-%d").to_string(),
+            tracked_secs,
+            sessions: sessions.len(),
+            context_switches,
+            median_session_secs: median(&lengths),
+            longest_session_secs: lengths.last().copied().unwrap_or(0),
+            short_session_share: share(short_secs, tracked_secs),
+            focus_score: (share(focus_secs, tracked_secs) * 100.0).round() as u8,
+        })
+    }
+
+    /// one line like the other lines of the pretty output.
+    pub fn text(&self) -> String {
+        format!(
+            "focus score {}%, {} switches, longest session {}, median {}, {:.0}% in short sessions",
+            self.focus_score,
+            self.context_switches,
+            secs_to_pretty(self.longest_session_secs),
+            secs_to_pretty(self.median_session_secs),
+            self.short_session_share * 100.0
+        )
+    }
+}
+
+/// The focus of every day in a range and of the whole range.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Focus {
+    pub from: String,
+    pub to: String,
+    pub days: Vec<DayFocus>,
+    pub context_switches: usize,
+    /// tracked time weighted average of the day scores.
+    pub focus_score: u8,
+}
+
+impl Focus {
+    /// the range is checked like the one of a report.
+    pub fn new(tasks: &[Activity], from: NaiveDate, to: NaiveDate) -> Result<Self, TrackerError> {
+        check_range(from, to)?;
+        let days = from
+            .iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| DayFocus::new(tasks, date))
+            .collect::<Result<Vec<DayFocus>, TrackerError>>()?;
+        let tracked_secs: SecType = days.iter().map(|d| d.tracked_secs).sum();
+        let weighted: f64 = days
+            .iter()
+            .map(|d| d.focus_score as f64 * d.tracked_secs as f64)
+            .sum();
+        Ok(Self {
+            from: from.format("%Y-%m-%d").to_string(),
+            to: to.format("%Y-%m-%d").to_string(),
+            context_switches: days.iter().map(|d| d.context_switches).sum(),
+            focus_score: if tracked_secs == 0 {
+                0
+            } else {
+                (weighted / tracked_secs as f64).round() as u8
+            },
+            days,
+        })
+    }
+}
+
+/// of sorted values.
+fn median(sorted: &[SecType]) -> SecType {
+    match sorted.len() {
+        0 => 0,
+        len if len % 2 == 1 => sorted[len / 2],
+        len => (sorted[len / 2 - 1] + sorted[len / 2]) / 2,
+    }
+}
+
+fn share(part: SecType, whole: SecType) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::parse_date;
+
+    fn task(name: &str, intervals: &[(SecType, SecType)]) -> Activity {
+        let mut task = Activity::new(name);
+        task.stop_at(0);
+        for (start, end) in intervals {
+            task.start_at(*start);
+            task.stop_at(*end);
+        }
+        task
+    }
+
+    #[test]
+    fn sessions_and_switches() {
+        let date = parse_date("2026-10-14").unwrap();
+        let nine = day_start(date) + 9 * 60 * 60;
+        let minute = 60;
+        let tasks = vec![
+            // 30 + 10 minutes that touch are one 40 minute session.
+            task(
+                "deep",
+                &[
+                    (nine, nine + 30 * minute),
+                    (nine + 30 * minute, nine + 40 * minute),
+                    (nine + 50 * minute, nine + 60 * minute),
+                ],
+            ),
+            task("mail", &[(nine + 40 * minute, nine + 50 * minute)]),
+        ];
+        let mut background = task("music", &[(nine, nine + 60 * minute)]);
+        background.set_concurrent(true);
+        let mut all = tasks.clone();
+        all.push(background);
+
+        let day = DayFocus::new(&all, date).unwrap();
+        assert_eq!(day.sessions, 3);
+        assert_eq!(day.context_switches, 2);
+        assert_eq!(day.tracked_secs, 60 * minute);
+        assert_eq!(day.longest_session_secs, 40 * minute);
+        assert_eq!(day.median_session_secs, 10 * minute);
+        assert_eq!(day.short_session_share, 20.0 / 60.0);
+        assert_eq!(day.focus_score, 67);
+        assert!(day.text().starts_with("focus score 67%, 2 switches"));
+
+        let focus = Focus::new(&tasks, date, date.succ_opt().unwrap()).unwrap();
+        assert_eq!(focus.days.len(), 2);
+        assert_eq!(
+            focus.days[1],
+            DayFocus::new(&[], date.succ_opt().unwrap()).unwrap()
+        );
+        assert_eq!(focus.focus_score, 67);
+        assert_eq!(median(&[1, 2, 3, 10]), 2);
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        let date = parse_date("2026-10-14").unwrap();
+        let earlier = parse_date("2026-10-13").unwrap();
+        assert!(Focus::new(&[], date, earlier).is_err());
+        assert!(Focus::new(&[], parse_date("2000-01-01").unwrap(), date).is_err());
+        assert!(DayFocus::new(&[], NaiveDate::MAX).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+use super::error::TrackerError;
+use super::rounding::Rounding;
+use super::schedule::Schedule;
+use super::task::*;
+use serde::{Deserialize, Serialize};
+use std::io::prelude::*;
+
+/// layout version of the archive. Restore takes this version and older.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// The settings the server runs with.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Settings {
+    pub rounding: Rounding,
+    pub concurrent: bool,
+}
+
+/// Everything stored, in one archive.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Backup {
+    pub version: u32,
+    /// timestamp of when the backup was made.
+    pub created: SecType,
+    pub settings: Settings,
+    /// the activities with their intervals.
+    pub activities: Vec<Activity>,
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
+}
+
+impl Backup {
+    /// checks an archive before it replaces the store.
+    pub fn validate(&self) -> Result<(), TrackerError> {
+        if self.version == 0 || self.version > BACKUP_VERSION {
+            return Err(TrackerError::InvalidValue(format!(
+                "backup version {} is not supported, the latest is {}",
+                self.version, BACKUP_VERSION
+            )));
+        }
+        for (index, task) in self.activities.iter().enumerate() {
+            task.validate()?;
+            if self.activities[..index]
+                .iter()
+                .any(|other| other.name() == task.name())
+            {
+                return Err(TrackerError::InvalidValue(format!(
+                    "{} is in the backup twice",
+                    task.name()
+                )));
+            }
+            if task.id() != 0
+                && self.activities[..index]
+                    .iter()
+                    .any(|other| other.id() == task.id())
+            {
+                return Err(TrackerError::InvalidValue(format!(
+                    "id {} is in the backup twice",
+                    task.id()
+                )));
+            }
+        }
+        for (index, schedule) in self.schedules.iter().enumerate() {
+            schedule.validate()?;
+            if self.schedules[..index].iter().any(|s| s.id == schedule.id) {
+                return Err(TrackerError::InvalidValue(format!(
+                    "schedule {} is in the backup twice",
+                    schedule.id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// writes a temporary file next to `path` and renames it over `path`,
+/// so a crash leaves either the old or the new file, never a part.
+pub fn write_atomic(path: &std::path::Path, text: &str) -> std::io::Result<()> {
+    let temp = temp_path(path);
+    write_synced(&temp, text)?;
+    std::fs::rename(&temp, path)
+}
+
+/// writes the files like write_atomic, all of them or none: the
+/// files are renamed into place only once every one is written.
+pub fn write_all_atomic(files: &[(std::path::PathBuf, String)]) -> std::io::Result<()> {
+    let mut staged = Vec::new();
+    for (path, text) in files {
+        let temp = temp_path(path);
+        let written = write_synced(&temp, text);
+        staged.push(temp);
+        if let Err(error) = written {
+            for temp in staged {
+                let _ = std::fs::remove_file(temp);
+            }
+            return Err(error);
+        }
+    }
+    for (temp, (path, _)) in staged.iter().zip(files) {
+        std::fs::rename(temp, path)?;
+    }
+    Ok(())
+}
+
+fn temp_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::path::PathBuf::from(temp)
+}
+
+fn write_synced(path: &std::path::Path, text: &str) -> std::io::Result<()> {
+    let mut file_handle = std::fs::File::create(path)?;
+    file_handle.write_all(text.as_bytes())?;
+    file_handle.sync_all()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::schedule::Action;
+
+    fn backup(activities: Vec<Activity>, schedules: Vec<Schedule>) -> Backup {
+        Backup {
+            version: BACKUP_VERSION,
+            created: 0,
+            settings: Settings {
+                rounding: Rounding::default(),
+                concurrent: false,
+            },
+            activities,
+            schedules,
+        }
+    }
+
+    #[test]
+    fn validating_backups() {
+        let mut task = Activity::new("review");
+        task.stop_at(0);
+        task.start_at(100);
+        task.stop_at(200);
+        let schedule = Schedule::new(1, "09:00", Action::Stop, true, 0).unwrap();
+        let valid = backup(vec![task.clone()], vec![schedule.clone()]);
+        assert_eq!(valid.validate(), Ok(()));
+
+        let text = serde_json::to_string(&valid).unwrap();
+        let read: Backup = serde_json::from_str(&text).unwrap();
+        assert_eq!(read, valid);
+
+        let mut newer = valid.clone();
+        newer.version = BACKUP_VERSION + 1;
+        assert!(newer.validate().is_err());
+        assert!(backup(vec![task.clone(), task.clone()], Vec::new())
+            .validate()
+            .is_err());
+        assert!(backup(Vec::new(), vec![schedule.clone(), schedule])
+            .validate()
+            .is_err());
+
+        let broken = text.replace("\"end\":200", "\"end\":50");
+        let read: Backup = serde_json::from_str(&broken).unwrap();
+        assert!(read.validate().is_err());
+    }
+
+    #[test]
+    fn writing_all_or_nothing() {
+        let dir = std::env::temp_dir().join(format!("timetracker-{}-all", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let kept = dir.join("kept.json");
+        std::fs::write(&kept, "old").unwrap();
+        let files = [
+            (kept.clone(), "new".to_string()),
+            (dir.join("missing").join("db.json"), "new".to_string()),
+        ];
+        assert!(write_all_atomic(&files).is_err());
+        assert_eq!(std::fs::read_to_string(&kept).unwrap(), "old");
+        assert!(!temp_path(&kept).exists());
+
+        write_all_atomic(&files[..1]).unwrap();
+        assert_eq!(std::fs::read_to_string(&kept).unwrap(), "new");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
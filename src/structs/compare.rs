@@ -0,0 +1,261 @@
+use super::report::{signed_secs_to_pretty, Period, Report};
+use super::task::*;
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// What a period is compared with.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Baseline {
+    /// the period right before, like last week for this week.
+    Previous,
+    /// the same period a year earlier.
+    LastYear,
+}
+
+impl std::str::FromStr for Baseline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "previous" => Ok(Baseline::Previous),
+            "last_year" => Ok(Baseline::LastYear),
+            _ => Err(format!("compare can be previous or last_year, got: {}", s)),
+        }
+    }
+}
+
+impl Baseline {
+    /// the range to compare a period's range with. Ranges that are not
+    /// a whole period, like explicit from - to ranges, are moved back by
+    /// their own length.
+    pub fn range(&self, period: Period, from: NaiveDate, to: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            Baseline::LastYear => {
+                let whole = period.range(from) == (from, to);
+                match period {
+                    // the same weekdays, 52 weeks earlier.
+                    Period::Week if whole => (from - Duration::weeks(52), to - Duration::weeks(52)),
+                    Period::Month if whole => period.range(year_earlier(from)),
+                    _ => (year_earlier(from), year_earlier(to)),
+                }
+            }
+            Baseline::Previous => {
+                if period.range(from) == (from, to) {
+                    period.range(from - Duration::days(1))
+                } else {
+                    let days = (to - from).num_days() + 1;
+                    (from - Duration::days(days), to - Duration::days(days))
+                }
+            }
+        }
+    }
+}
+
+/// the same day a year earlier, the 29th of february becomes the 28th.
+fn year_earlier(date: NaiveDate) -> NaiveDate {
+    date.with_year(date.year() - 1)
+        .unwrap_or_else(|| NaiveDate::from_ymd_opt(date.year() - 1, 2, 28).unwrap())
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Change {
+    pub name: String,
+    pub current_secs: SecType,
+    pub previous_secs: SecType,
+    pub delta_secs: i64,
+    pub delta_pretty: String,
+    /// None when there was no time before.
+    pub change_percent: Option<f64>,
+}
+
+impl Change {
+    fn new(name: &str, current_secs: SecType, previous_secs: SecType) -> Self {
+        let delta_secs = current_secs as i64 - previous_secs as i64;
+        Self {
+            name: name.to_string(),
+            current_secs,
+            previous_secs,
+            delta_secs,
+            delta_pretty: signed_secs_to_pretty(delta_secs),
+            change_percent: if previous_secs == 0 {
+                None
+            } else {
+                Some((delta_secs as f64 * 1000.0 / previous_secs as f64).round() / 10.0)
+            },
+        }
+    }
+}
+
+/// Two reports side by side with the changes per activity and project.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Comparison {
+    pub current_from: String,
+    pub current_to: String,
+    pub previous_from: String,
+    pub previous_to: String,
+    /// biggest growth first.
+    pub activities: Vec<Change>,
+    pub projects: Vec<Change>,
+    pub total: Change,
+}
+
+impl Comparison {
+    pub fn new(current: &Report, previous: &Report) -> Self {
+        let mut activities = changes(
+            current
+                .activities
+                .iter()
+                .map(|a| (a.name.to_owned(), a.secs)),
+            previous
+                .activities
+                .iter()
+                .map(|a| (a.name.to_owned(), a.secs)),
+        );
+        activities.sort_by(|a, b| b.delta_secs.cmp(&a.delta_secs).then(a.name.cmp(&b.name)));
+        let mut projects = changes(
+            current
+                .projects
+                .iter()
+                .map(|p| (p.project.to_owned(), p.secs)),
+            previous
+                .projects
+                .iter()
+                .map(|p| (p.project.to_owned(), p.secs)),
+        );
+        projects.sort_by(|a, b| b.delta_secs.cmp(&a.delta_secs).then(a.name.cmp(&b.name)));
+        Self {
+            current_from: current.from.to_owned(),
+            current_to: current.to.to_owned(),
+            previous_from: previous.from.to_owned(),
+            previous_to: previous.to.to_owned(),
+            activities,
+            projects,
+            total: Change::new("total", current.total_secs, previous.total_secs),
+        }
+    }
+
+    /// a text table like the report text.
+    pub fn text(&self) -> String {
+        let mut result = format!(
+            "comparing {} - {} with {} - {}",
+            self.current_from, self.current_to, self.previous_from, self.previous_to
+        );
+        result.push_str(&format!("\n\n  {}", change_text(&self.total)));
+        result.push_str("\n\nactivities:");
+        for change in self.activities.iter() {
+            result.push_str(&format!("\n  {}", change_text(change)));
+        }
+        result.push_str("\n\nprojects:");
+        for change in self.projects.iter() {
+            result.push_str(&format!("\n  {}", change_text(change)));
+        }
+        result.push('\n');
+        result
+    }
+}
+
+fn changes<C, P>(current: C, previous: P) -> Vec<Change>
+where
+    C: Iterator<Item = (String, SecType)>,
+    P: Iterator<Item = (String, SecType)>,
+{
+    let mut secs: BTreeMap<String, (SecType, SecType)> = BTreeMap::new();
+    for (name, current) in current {
+        secs.entry(name).or_insert((0, 0)).0 += current;
+    }
+    for (name, previous) in previous {
+        secs.entry(name).or_insert((0, 0)).1 += previous;
+    }
+    secs.into_iter()
+        .map(|(name, (current, previous))| Change::new(&name, current, previous))
+        .collect()
+}
+
+fn change_text(change: &Change) -> String {
+    let percent = match change.change_percent {
+        Some(percent) => format!("{:+.1}%", percent),
+        None => "new".to_string(),
+    };
+    format!(
+        "{} -> {} ({}, {})",
+        name_time_text(&change.name, change.previous_secs),
+        secs_to_pretty(change.current_secs),
+        change.delta_pretty,
+        percent
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::{day_start, parse_date};
+    use crate::structs::report::NO_PROJECT;
+    use crate::structs::rounding::Rounding;
+
+    fn date(text: &str) -> NaiveDate {
+        parse_date(text).unwrap()
+    }
+
+    #[test]
+    fn baseline_ranges() {
+        let week = Period::Week.range(date("2026-10-15"));
+        assert_eq!(
+            Baseline::Previous.range(Period::Week, week.0, week.1),
+            (date("2026-10-05"), date("2026-10-11"))
+        );
+        let month = Period::Month.range(date("2026-03-15"));
+        assert_eq!(
+            Baseline::Previous.range(Period::Month, month.0, month.1),
+            (date("2026-02-01"), date("2026-02-28"))
+        );
+        assert_eq!(
+            Baseline::LastYear.range(Period::Month, month.0, month.1),
+            (date("2025-03-01"), date("2025-03-31"))
+        );
+        assert_eq!(
+            Baseline::Previous.range(Period::Day, date("2026-10-01"), date("2026-10-10")),
+            (date("2026-09-21"), date("2026-09-30"))
+        );
+        assert_eq!(
+            Baseline::LastYear.range(Period::Week, week.0, week.1),
+            (date("2025-10-13"), date("2025-10-19"))
+        );
+        assert_eq!(year_earlier(date("2028-02-29")), date("2027-02-28"));
+        assert!("tomorrow".parse::<Baseline>().is_err());
+    }
+
+    #[test]
+    fn changes_per_activity() {
+        let monday = date("2026-10-12");
+        let previous_monday = date("2026-10-05");
+        let mut alpha = Activity::new("alpha");
+        alpha.set_project("billing");
+        alpha.stop_at(0);
+        alpha.start_at(day_start(previous_monday));
+        alpha.stop_at(day_start(previous_monday) + 3600);
+        alpha.start_at(day_start(monday));
+        alpha.stop_at(day_start(monday) + 5400);
+        let mut beta = Activity::new("beta");
+        beta.stop_at(0);
+        beta.start_at(day_start(monday) + 7200);
+        beta.stop_at(day_start(monday) + 7800);
+        let tasks = [alpha, beta];
+        let rounding = Rounding::default();
+        let current = Report::new(&tasks, monday, date("2026-10-18"), &rounding, 0).unwrap();
+        let previous =
+            Report::new(&tasks, previous_monday, date("2026-10-11"), &rounding, 0).unwrap();
+
+        let comparison = Comparison::new(&current, &previous);
+        assert_eq!(comparison.activities[0].name, "alpha");
+        assert_eq!(comparison.activities[0].delta_secs, 1800);
+        assert_eq!(comparison.activities[0].change_percent, Some(50.0));
+        assert_eq!(comparison.activities[1].name, "beta");
+        assert_eq!(comparison.activities[1].change_percent, None);
+        assert_eq!(comparison.projects[0].name, "billing");
+        assert_eq!(comparison.projects[1].name, NO_PROJECT);
+        assert_eq!(comparison.total.delta_secs, 2400);
+        assert!(comparison.text().contains("(+00h:30m, +50.0%)"));
+        assert!(comparison.text().contains("(+00h:10m, new)"));
+    }
+}
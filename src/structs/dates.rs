@@ -0,0 +1,273 @@
+use super::error::TrackerError;
+use super::task::SecType;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+
+/// The time an action happened at. `at` is an absolute time,
+/// `ago` is a duration back from now. Without either it's now.
+pub fn resolve_time(
+    at: Option<&str>,
+    ago: Option<&str>,
+    now: SecType,
+) -> Result<SecType, TrackerError> {
+    let t = match (at, ago) {
+        (Some(_), Some(_)) => {
+            return Err(TrackerError::InvalidTime(
+                "use either at or ago, not both".to_string(),
+            ))
+        }
+        (Some(at), None) => parse_at(at, now)?,
+        (None, Some(ago)) => now
+            .checked_sub(parse_duration(ago)?)
+            .ok_or_else(|| TrackerError::InvalidTime(format!("{} ago is before the epoch", ago)))?,
+        (None, None) => now,
+    };
+    if t > now {
+        return Err(TrackerError::InvalidTime(format!(
+            "{} is in the future",
+            local_pretty(t)
+        )));
+    }
+    Ok(t)
+}
+
+/// Accepts unix seconds, RFC 3339, "YYYY-MM-DD HH:MM" and
+/// "HH:MM" which is taken as today in local time. Seconds past
+/// the dates chrono can show are refused.
+pub fn parse_at(text: &str, now: SecType) -> Result<SecType, TrackerError> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<SecType>() {
+        return check_secs(secs, text);
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return to_secs(datetime.timestamp(), text);
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M") {
+        return local_to_secs(&naive, text);
+    }
+    if let Ok(time) = NaiveTime::parse_from_str(text, "%H:%M") {
+        return local_to_secs(&local_date(now).and_time(time), text);
+    }
+    Err(TrackerError::InvalidTime(format!(
+        "can't read time: {}",
+        text
+    )))
+}
+
+/// "90s", "15m", "1h30m" or a plain number of minutes.
+pub fn parse_duration(text: &str) -> Result<SecType, TrackerError> {
+    let text = text.trim();
+    let invalid = || TrackerError::InvalidTime(format!("can't read duration: {}", text));
+    let too_long = || TrackerError::InvalidTime(format!("duration is too long: {}", text));
+    if let Ok(minutes) = text.parse::<SecType>() {
+        return minutes.checked_mul(60).ok_or_else(too_long);
+    }
+    let mut total: SecType = 0;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let value: SecType = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        let unit = match c {
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        total = value
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(too_long)?;
+    }
+    if !number.is_empty() || text.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// "YYYY-MM-DD".
+pub fn parse_date(text: &str) -> Result<NaiveDate, TrackerError> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+        .map_err(|_| TrackerError::InvalidTime(format!("expected YYYY-MM-DD, got: {}", text)))
+}
+
+/// the monday of an ISO week like "2026-W42", or of the week
+/// a "YYYY-MM-DD" date is in.
+pub fn parse_week(text: &str) -> Result<NaiveDate, TrackerError> {
+    let text = text.trim();
+    let error = || TrackerError::InvalidTime(format!("expected YYYY-Www, got: {}", text));
+    if let Some((year, week)) = text.split_once("-W") {
+        let year = year.parse::<i32>().map_err(|_| error())?;
+        let week = week.parse::<u32>().map_err(|_| error())?;
+        return NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).ok_or_else(error);
+    }
+    let date = parse_date(text).map_err(|_| error())?;
+    Ok(
+        NaiveDate::from_isoywd_opt(date.iso_week().year(), date.iso_week().week(), Weekday::Mon)
+            .unwrap(),
+    )
+}
+
+/// longest range of dates reports go through day by day.
+pub const MAX_RANGE_DAYS: i64 = 3 * 366;
+
+/// checks a range of dates, both included: from can't be after to and
+/// the range can be at most MAX_RANGE_DAYS long.
+pub fn check_range(from: NaiveDate, to: NaiveDate) -> Result<(), TrackerError> {
+    if from > to {
+        return Err(TrackerError::InvalidTime(format!(
+            "from {} is after to {}",
+            from, to
+        )));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(TrackerError::InvalidTime(format!(
+            "{} - {} is longer than {} days",
+            from, to, MAX_RANGE_DAYS
+        )));
+    }
+    Ok(())
+}
+
+/// the date after, an error at the end of the calendar.
+pub fn next_day(date: NaiveDate) -> Result<NaiveDate, TrackerError> {
+    date.succ_opt()
+        .ok_or_else(|| TrackerError::InvalidTime(format!("no date after {}", date)))
+}
+
+/// the local calendar date of a timestamp.
+pub fn local_date(secs: SecType) -> NaiveDate {
+    Local
+        .timestamp_opt(secs as i64, 0)
+        .unwrap()
+        .naive_local()
+        .date()
+}
+
+/// timestamp of the local midnight starting the date.
+pub fn day_start(date: NaiveDate) -> SecType {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|datetime| datetime.timestamp().max(0) as SecType)
+        .unwrap_or(0)
+}
+
+/// the local time, or the plain seconds for times chrono can't show.
+pub fn local_pretty(secs: SecType) -> String {
+    match i64::try_from(secs).map(|secs| Local.timestamp_opt(secs, 0).single()) {
+        Ok(Some(datetime)) => format!("{}", datetime),
+        _ => format!("{}s", secs),
+    }
+}
+
+/// a local date and time as a timestamp, `text` is used in the errors.
+pub fn local_to_secs(naive: &NaiveDateTime, text: &str) -> Result<SecType, TrackerError> {
+    match Local.from_local_datetime(naive).earliest() {
+        Some(datetime) => to_secs(datetime.timestamp(), text),
+        None => Err(TrackerError::InvalidTime(format!(
+            "{} doesn't exist in local time",
+            text
+        ))),
+    }
+}
+
+/// the timestamp if it is a local time chrono can represent.
+fn check_secs(secs: SecType, text: &str) -> Result<SecType, TrackerError> {
+    match i64::try_from(secs).map(|secs| Local.timestamp_opt(secs, 0).single()) {
+        Ok(Some(_)) => Ok(secs),
+        _ => Err(TrackerError::InvalidTime(format!(
+            "{} is too far in the future",
+            text
+        ))),
+    }
+}
+
+fn to_secs(timestamp: i64, text: &str) -> Result<SecType, TrackerError> {
+    SecType::try_from(timestamp)
+        .map_err(|_| TrackerError::InvalidTime(format!("{} is before the epoch", text)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("15"), Ok(15 * 60));
+        assert_eq!(parse_duration("15m"), Ok(15 * 60));
+        assert_eq!(parse_duration("90s"), Ok(90));
+        assert_eq!(parse_duration("1h30m"), Ok(90 * 60));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1x").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("999999999999999999").is_err());
+        assert!(parse_duration("999999999999999999h").is_err());
+        assert!(parse_duration("18446744073709551615s1s").is_err());
+        assert!(resolve_time(None, Some("999999999999999999"), 1000).is_err());
+    }
+
+    #[test]
+    fn absolute_times() {
+        let now = 1_700_000_000;
+        assert_eq!(parse_at("1600000000", now), Ok(1_600_000_000));
+        assert_eq!(parse_at("2023-11-14T22:13:20Z", now), Ok(now));
+        assert_eq!(parse_at("2023-11-14T23:13:20+01:00", now), Ok(now));
+        let today_noon = parse_at("12:00", now).unwrap();
+        assert_eq!(
+            Local
+                .timestamp_opt(today_noon as i64, 0)
+                .unwrap()
+                .naive_local()
+                .date(),
+            Local
+                .timestamp_opt(now as i64, 0)
+                .unwrap()
+                .naive_local()
+                .date()
+        );
+        assert!(parse_at("yesterday", now).is_err());
+        assert!(parse_at("9999999999999", now).is_err());
+        assert!(parse_at("18446744073709551615", now).is_err());
+        assert!(resolve_time(Some("9999999999999"), None, now).is_err());
+        assert_eq!(local_pretty(SecType::MAX), format!("{}s", SecType::MAX));
+    }
+
+    #[test]
+    fn days() {
+        let date = parse_date("2026-10-18").unwrap();
+        assert_eq!(local_date(day_start(date)), date);
+        assert_eq!(local_date(day_start(date) - 1), date.pred_opt().unwrap());
+        assert!(parse_date("18/10/2026").is_err());
+        assert!(check_range(date, date).is_ok());
+        assert!(check_range(date, date.pred_opt().unwrap()).is_err());
+        assert!(check_range(parse_date("0001-01-01").unwrap(), date).is_err());
+        assert!(next_day(NaiveDate::MAX).is_err());
+    }
+
+    #[test]
+    fn weeks() {
+        let monday = parse_date("2026-10-12").unwrap();
+        assert_eq!(parse_week("2026-W42"), Ok(monday));
+        assert_eq!(parse_week("2026-10-18"), Ok(monday));
+        assert_eq!(parse_week("2027-01-01"), parse_week("2026-W53"));
+        assert!(parse_week("2026-W54").is_err());
+        assert!(parse_week("W42").is_err());
+    }
+
+    #[test]
+    fn resolving_times() {
+        let now = 1_700_000_000;
+        assert_eq!(resolve_time(None, None, now), Ok(now));
+        assert_eq!(resolve_time(None, Some("15m"), now), Ok(now - 15 * 60));
+        assert_eq!(
+            resolve_time(Some("1600000000"), None, now),
+            Ok(1_600_000_000)
+        );
+        assert!(resolve_time(Some("1800000000"), None, now).is_err());
+        assert!(resolve_time(Some("1600000000"), Some("1m"), now).is_err());
+    }
+}
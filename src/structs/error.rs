@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Errors reported back to the caller instead of panicking.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TrackerError {
+    /// a time that can't be parsed or would break the recorded intervals.
+    InvalidTime(String),
+    /// a value in a request that doesn't make sense.
+    InvalidValue(String),
+    /// the requested item doesn't exist.
+    NotFound(String),
+    /// the request clashes with what is stored, like a taken name.
+    Conflict(String),
+    /// the store can't be read or written.
+    Storage(String),
+}
+
+impl TrackerError {
+    /// short name of the kind of error for json responses.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TrackerError::InvalidTime(_) => "invalid_time",
+            TrackerError::InvalidValue(_) => "invalid_value",
+            TrackerError::NotFound(_) => "not_found",
+            TrackerError::Conflict(_) => "conflict",
+            TrackerError::Storage(_) => "storage",
+        }
+    }
+}
+
+impl fmt::Display for TrackerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackerError::InvalidTime(message) => write!(f, "invalid time: {}", message),
+            TrackerError::InvalidValue(message) => write!(f, "invalid value: {}", message),
+            TrackerError::NotFound(message) => write!(f, "not found: {}", message),
+            TrackerError::Conflict(message) => write!(f, "conflict: {}", message),
+            TrackerError::Storage(message) => write!(f, "storage error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TrackerError {}
@@ -0,0 +1,226 @@
+use super::dates::{day_start, parse_date};
+use super::error::TrackerError;
+use super::task::*;
+use chrono::NaiveDate;
+use regex::Regex;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Sort {
+    /// the order the tasks were added in.
+    Added,
+    /// most tracked time first.
+    Total,
+    /// most recently started or stopped first.
+    LastUsed,
+    /// by name.
+    Name,
+}
+
+impl std::str::FromStr for Sort {
+    type Err = TrackerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "added" => Ok(Sort::Added),
+            "total" => Ok(Sort::Total),
+            "last_used" => Ok(Sort::LastUsed),
+            "name" => Ok(Sort::Name),
+            _ => Err(TrackerError::InvalidValue(format!(
+                "sort can be added, total, last_used or name, got: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// A compiled name pattern, compared by its text.
+#[derive(Clone, Debug)]
+pub struct Pattern(Regex);
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// Which tasks are listed and in what order. The default lists all
+/// of them in the order they were added.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TaskFilter {
+    /// part of the name, case insensitive.
+    pub name: Option<String>,
+    pub regex: Option<Pattern>,
+    pub tag: Option<String>,
+    pub project: Option<String>,
+    pub active_only: bool,
+    /// only tasks with time tracked on or after this date.
+    pub from: Option<NaiveDate>,
+    /// only tasks with time tracked on or before this date.
+    pub to: Option<NaiveDate>,
+    pub sort: Sort,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl Default for TaskFilter {
+    fn default() -> Self {
+        Self {
+            name: None,
+            regex: None,
+            tag: None,
+            project: None,
+            active_only: false,
+            from: None,
+            to: None,
+            sort: Sort::Added,
+            offset: 0,
+            limit: None,
+        }
+    }
+}
+
+impl TaskFilter {
+    pub fn set_regex(&mut self, pattern: &str) -> Result<(), TrackerError> {
+        let regex = Regex::new(pattern)
+            .map_err(|error| TrackerError::InvalidValue(format!("invalid regex: {}", error)))?;
+        self.regex = Some(Pattern(regex));
+        Ok(())
+    }
+
+    pub fn set_range(&mut self, from: Option<&str>, to: Option<&str>) -> Result<(), TrackerError> {
+        self.from = from.map(parse_date).transpose()?;
+        self.to = to.map(parse_date).transpose()?;
+        Ok(())
+    }
+
+    pub fn matches(&self, task: &Activity) -> bool {
+        if let Some(name) = &self.name {
+            if !task.name().to_lowercase().contains(&name.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(Pattern(regex)) = &self.regex {
+            if !regex.is_match(&task.name()) {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !task.tags().contains(tag) {
+                return false;
+            }
+        }
+        if let Some(project) = &self.project {
+            if task.project().as_ref() != Some(project) {
+                return false;
+            }
+        }
+        if self.active_only && !task.is_active() {
+            return false;
+        }
+        if self.from.is_some() || self.to.is_some() {
+            let start = self.from.map(day_start).unwrap_or(0);
+            let end = self
+                .to
+                .and_then(|to| to.succ_opt())
+                .map(day_start)
+                .unwrap_or(SecType::MAX);
+            if task.intervals_between(start, end).is_empty() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// the matching tasks sorted, then the requested page of them.
+    pub fn apply(&self, tasks: Vec<Activity>) -> Vec<Activity> {
+        let mut tasks: Vec<Activity> = tasks.into_iter().filter(|t| self.matches(t)).collect();
+        match self.sort {
+            Sort::Added => {}
+            Sort::Total => tasks.sort_by_key(|t| std::cmp::Reverse(t.secs_since_creation())),
+            Sort::LastUsed => tasks.sort_by_key(|t| std::cmp::Reverse(t.last_change())),
+            Sort::Name => tasks.sort_by_key(|t| t.name().to_lowercase()),
+        }
+        tasks
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task(name: &str, start: SecType, end: SecType) -> Activity {
+        let mut task = Activity::new(name);
+        task.stop_at(0);
+        task.start_at(start);
+        task.stop_at(end);
+        task
+    }
+
+    fn names(tasks: &[Activity]) -> Vec<String> {
+        tasks.iter().map(|t| t.name()).collect()
+    }
+
+    #[test]
+    fn filtering_tasks() {
+        let mut review = task("Code review", 100, 400);
+        review.set_tags(&["work".to_string()]);
+        review.set_project("billing");
+        let tasks = vec![review, task("lunch", 500, 600), task("code golf", 700, 750)];
+
+        let mut filter = TaskFilter {
+            name: Some("CODE".to_string()),
+            ..TaskFilter::default()
+        };
+        assert_eq!(
+            names(&filter.apply(tasks.clone())),
+            ["Code review", "code golf"]
+        );
+        filter.tag = Some("work".to_string());
+        assert_eq!(names(&filter.apply(tasks.clone())), ["Code review"]);
+
+        let mut filter = TaskFilter::default();
+        filter.set_regex("^[a-z]").unwrap();
+        assert_eq!(names(&filter.apply(tasks.clone())), ["lunch", "code golf"]);
+        assert!(filter.set_regex("(").is_err());
+
+        let filter = TaskFilter {
+            project: Some("billing".to_string()),
+            ..TaskFilter::default()
+        };
+        assert_eq!(names(&filter.apply(tasks.clone())), ["Code review"]);
+        let filter = TaskFilter {
+            active_only: true,
+            ..TaskFilter::default()
+        };
+        assert!(filter.apply(tasks.clone()).is_empty());
+
+        let mut filter = TaskFilter::default();
+        filter.set_range(Some("2026-10-12"), None).unwrap();
+        assert!(filter.apply(tasks).is_empty());
+    }
+
+    #[test]
+    fn sorting_and_pages() {
+        let tasks = vec![
+            task("b", 100, 400),
+            task("c", 500, 600),
+            task("a", 700, 750),
+        ];
+        let mut filter = TaskFilter {
+            sort: Sort::Total,
+            ..TaskFilter::default()
+        };
+        assert_eq!(names(&filter.apply(tasks.clone())), ["b", "c", "a"]);
+        filter.sort = Sort::LastUsed;
+        assert_eq!(names(&filter.apply(tasks.clone())), ["a", "c", "b"]);
+        filter.sort = Sort::Name;
+        filter.offset = 1;
+        filter.limit = Some(1);
+        assert_eq!(names(&filter.apply(tasks)), ["b"]);
+        assert!("oldest".parse::<Sort>().is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use super::task::SecType;
+use chrono::{Local, TimeZone};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// how many switches are remembered.
+const HISTORY_LENGTH: usize = 100;
+
+/// One change of the running activity.
+/// `None` means no activity was running.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Switch {
+    pub at: SecType,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// The latest switches, newest last.
+/// The history is only kept in memory, it starts empty
+/// every time the server starts.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct History {
+    switches: VecDeque<Switch>,
+}
+
+impl Serialize for Switch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Switch", 4)?;
+        state.serialize_field("at", &self.at)?;
+        let at_pretty = format!("{}", Local.timestamp_opt(self.at as i64, 0).unwrap());
+        state.serialize_field("at_pretty", &at_pretty)?;
+        state.serialize_field("from", &self.from)?;
+        state.serialize_field("to", &self.to)?;
+        state.end()
+    }
+}
+
+impl History {
+    pub fn push(&mut self, switch: Switch) {
+        if switch.from == switch.to {
+            return;
+        }
+        if self.switches.len() == HISTORY_LENGTH {
+            self.switches.pop_front();
+        }
+        self.switches.push_back(switch);
+    }
+
+    /// follows an activity that got a new name.
+    pub fn rename(&mut self, old: &str, new: &str) {
+        for switch in self.switches.iter_mut() {
+            for name in [&mut switch.from, &mut switch.to].into_iter().flatten() {
+                if name == old {
+                    *name = new.to_string();
+                }
+            }
+        }
+    }
+
+    /// drops the switches from or to a deleted activity.
+    pub fn forget(&mut self, old: &str) {
+        self.switches.retain(|switch| {
+            switch.from.as_deref() != Some(old) && switch.to.as_deref() != Some(old)
+        });
+    }
+
+    /// newest first.
+    pub fn latest(&self, limit: usize) -> Vec<Switch> {
+        self.switches.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// the activity that was left most recently and
+    /// isn't one of the `running` ones.
+    pub fn previous(&self, running: &[String]) -> Option<String> {
+        self.switches
+            .iter()
+            .rev()
+            .filter_map(|switch| switch.from.to_owned())
+            .find(|name| !running.contains(name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn switch(from: Option<&str>, to: Option<&str>) -> Switch {
+        Switch {
+            at: 0,
+            from: from.map(String::from),
+            to: to.map(String::from),
+        }
+    }
+
+    #[test]
+    fn previous_activity() {
+        let mut history = History::default();
+        assert_eq!(history.previous(&[]), None);
+        history.push(switch(None, Some("a")));
+        assert_eq!(history.previous(&["a".to_string()]), None);
+        history.push(switch(Some("a"), Some("b")));
+        assert_eq!(history.previous(&["b".to_string()]), Some("a".to_string()));
+        history.push(switch(Some("b"), None));
+        assert_eq!(history.previous(&[]), Some("b".to_string()));
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut history = History::default();
+        for _ in 0..HISTORY_LENGTH {
+            history.push(switch(Some("a"), Some("b")));
+        }
+        history.push(switch(Some("c"), Some("d")));
+        history.push(switch(Some("d"), Some("d")));
+        assert_eq!(history.latest(usize::MAX).len(), HISTORY_LENGTH);
+        assert_eq!(history.latest(1), vec![switch(Some("c"), Some("d"))]);
+    }
+}
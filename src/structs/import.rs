@@ -0,0 +1,232 @@
+use super::dates::local_pretty;
+use super::task::*;
+use serde::Serialize;
+
+/// name of entries that don't name an activity.
+pub const UNNAMED: &str = "imported";
+
+/// One interval read from an other tracker's export.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct ImportEntry {
+    pub name: String,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub start: SecType,
+    pub end: SecType,
+    /// the export cuts its times to whole multiples of this many secs,
+    /// 1 when it has the seconds.
+    pub precision: SecType,
+}
+
+impl ImportEntry {
+    fn text(&self) -> String {
+        format!(
+            "{}: {} - {}",
+            self.name,
+            local_pretty(self.start),
+            local_pretty(self.end)
+        )
+    }
+}
+
+/// The entries read from an export and the rows of it that can't
+/// be read, with the reason.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ImportFile {
+    pub entries: Vec<ImportEntry>,
+    pub skipped: Vec<String>,
+}
+
+impl From<Vec<ImportEntry>> for ImportFile {
+    fn from(entries: Vec<ImportEntry>) -> Self {
+        Self {
+            entries,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+/// What an import created, or would create on a dry run.
+#[derive(Clone, PartialEq, Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub dry_run: bool,
+    pub activities: Vec<String>,
+    pub projects: Vec<String>,
+    pub tags: Vec<String>,
+    pub intervals: usize,
+    /// entries with the same start and end as a stored interval, cut
+    /// like the times of the export.
+    pub duplicates: Vec<String>,
+    /// entries that can't be added, with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl ImportSummary {
+    pub fn text(&self) -> String {
+        let mut result = String::new();
+        if self.dry_run {
+            result.push_str("dry run, nothing was saved.\n");
+        }
+        result.push_str(&format!("intervals:  {}\n", self.intervals));
+        for (title, names) in [
+            ("activities", &self.activities),
+            ("projects", &self.projects),
+            ("tags", &self.tags),
+        ] {
+            result.push_str(&format!("new {}: {}\n", title, names.join(", ")));
+        }
+        result.push_str(&format!("duplicates: {}\n", self.duplicates.len()));
+        for duplicate in self.duplicates.iter() {
+            result.push_str(&format!("  {}\n", duplicate));
+        }
+        result.push_str(&format!("skipped:    {}\n", self.skipped.len()));
+        for skipped in self.skipped.iter() {
+            result.push_str(&format!("  {}\n", skipped));
+        }
+        result
+    }
+}
+
+/// Adds the entries to the tasks. New activities are created stopped,
+/// an activity without a project or notes gets the ones of its entries
+/// and the tags of the entries are added to the activity's tags.
+pub fn import(tasks: &mut Vec<Activity>, entries: &[ImportEntry], now: SecType) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let known_projects: Vec<String> = tasks.iter().filter_map(|t| t.project()).collect();
+    let known_tags: Vec<String> = tasks.iter().flat_map(|t| t.tags()).collect();
+
+    for entry in entries {
+        if entry.end > now {
+            summary
+                .skipped
+                .push(format!("{} (ends in the future)", entry.text()));
+            continue;
+        }
+        let names: Vec<String> = tasks.iter().map(|t| t.name()).collect();
+        let name = match resolve_name(&entry.name, &names) {
+            Ok(name) => name,
+            Err(error) => {
+                summary
+                    .skipped
+                    .push(format!("{} ({})", entry.text(), error));
+                continue;
+            }
+        };
+        let task = match tasks.iter_mut().position(|t| t.name() == name) {
+            Some(position) => &mut tasks[position],
+            None => {
+                let mut task = Activity::new(&name);
+                task.stop_at(now);
+                tasks.push(task);
+                summary.activities.push(name.to_owned());
+                tasks.last_mut().unwrap()
+            }
+        };
+        let interval = Interval {
+            start: entry.start,
+            end: entry.end,
+        };
+        let cut = |secs: SecType| secs - secs % entry.precision.max(1);
+        if task
+            .intervals()
+            .iter()
+            .any(|stored| cut(stored.start) == entry.start && cut(stored.end) == entry.end)
+        {
+            summary.duplicates.push(entry.text());
+            continue;
+        }
+        if let Err(error) = task.add_interval(interval) {
+            summary
+                .skipped
+                .push(format!("{} ({})", entry.text(), error));
+            continue;
+        }
+        summary.intervals += 1;
+        if let (None, Some(project)) = (task.project(), &entry.project) {
+            task.set_project(project);
+            if !known_projects.contains(project) && !summary.projects.contains(project) {
+                summary.projects.push(project.to_owned());
+            }
+        }
+        if let (None, Some(notes)) = (task.notes(), &entry.notes) {
+            task.set_notes(notes);
+        }
+        let mut tags = task.tags();
+        for tag in entry.tags.iter() {
+            if !tags.contains(tag) {
+                tags.push(tag.to_owned());
+            }
+            if !known_tags.contains(tag) && !summary.tags.contains(tag) {
+                summary.tags.push(tag.to_owned());
+            }
+        }
+        task.set_tags(&tags);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(name: &str, project: Option<&str>, start: SecType, end: SecType) -> ImportEntry {
+        ImportEntry {
+            name: name.to_string(),
+            project: project.map(String::from),
+            tags: vec!["imported".to_string()],
+            notes: None,
+            start,
+            end,
+            precision: 1,
+        }
+    }
+
+    #[test]
+    fn importing_entries() {
+        let mut existing = Activity::new("review");
+        existing.stop_at(0);
+        existing.start_at(1000);
+        existing.stop_at(2000);
+        let mut tasks = vec![existing];
+        let entries = vec![
+            entry("review", Some("billing"), 1000, 2000),
+            entry("review", Some("billing"), 1500, 2500),
+            entry("review", Some("billing"), 3000, 4000),
+            entry("mail", None, 4000, 4600),
+            entry("mail", None, 9000, 12000),
+            entry(" ", None, 5000, 5100),
+        ];
+        let summary = import(&mut tasks, &entries, 10000);
+        assert_eq!(summary.intervals, 2);
+        assert_eq!(summary.activities, ["mail"]);
+        assert_eq!(summary.projects, ["billing"]);
+        assert_eq!(summary.tags, ["imported"]);
+        assert_eq!(summary.duplicates.len(), 1);
+        assert_eq!(summary.skipped.len(), 3);
+        assert_eq!(tasks[0].secs_since_creation(), 2000);
+        assert_eq!(tasks[0].project(), Some("billing".to_string()));
+        assert_eq!(tasks[1].tags(), ["imported"]);
+        assert!(summary.text().contains("new activities: mail\n"));
+
+        let again = import(&mut tasks, &entries[..1], 10000);
+        assert_eq!(again.intervals, 0);
+        assert_eq!(again.duplicates.len(), 1);
+        assert!(again.projects.is_empty());
+    }
+
+    #[test]
+    fn duplicates_in_minutes() {
+        let mut existing = Activity::new("review");
+        existing.stop_at(0);
+        existing.start_at(6030);
+        existing.stop_at(6659);
+        let mut tasks = vec![existing];
+        let mut cut = entry("review", None, 6000, 6600);
+        assert_eq!(import(&mut tasks, &[cut.clone()], 10000).skipped.len(), 1);
+        cut.precision = 60;
+        let summary = import(&mut tasks, &[cut], 10000);
+        assert_eq!(summary.duplicates.len(), 1);
+        assert!(summary.skipped.is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+/// upper bounds of the request duration histogram in seconds.
+pub const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Request durations of one route, counts are per bucket,
+/// not cumulative. The last count is for the slower requests.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Histogram {
+    pub counts: [u64; BUCKETS.len() + 1],
+    pub sum: f64,
+    pub count: u64,
+}
+
+impl Histogram {
+    pub fn observe(&mut self, secs: f64) {
+        let bucket = BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(BUCKETS.len());
+        self.counts[bucket] += 1;
+        self.sum += secs;
+        self.count += 1;
+    }
+
+    /// (upper bound, requests up to it) pairs as prometheus expects them.
+    pub fn cumulative(&self) -> Vec<(f64, u64)> {
+        let mut total = 0;
+        BUCKETS
+            .iter()
+            .zip(self.counts.iter())
+            .map(|(bound, count)| {
+                total += count;
+                (*bound, total)
+            })
+            .collect()
+    }
+}
+
+/// Counts of the requests the server answered, kept in memory.
+/// Routes are the matched patterns like /api/start/{name} so every
+/// activity name doesn't get its own series.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct HttpMetrics {
+    /// (method, route, status) to count.
+    pub requests: BTreeMap<(String, String, u16), u64>,
+    /// (method, route) to durations.
+    pub durations: BTreeMap<(String, String), Histogram>,
+}
+
+impl HttpMetrics {
+    pub fn record(&mut self, method: &str, route: &str, status: u16, secs: f64) {
+        *self
+            .requests
+            .entry((method.to_string(), route.to_string(), status))
+            .or_insert(0) += 1;
+        self.durations
+            .entry((method.to_string(), route.to_string()))
+            .or_default()
+            .observe(secs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recording_requests() {
+        let mut metrics = HttpMetrics::default();
+        metrics.record("GET", "/api/times", 200, 0.002);
+        metrics.record("GET", "/api/times", 200, 0.3);
+        metrics.record("GET", "/api/times", 404, 20.0);
+        assert_eq!(
+            metrics.requests[&("GET".to_string(), "/api/times".to_string(), 200)],
+            2
+        );
+        let histogram = &metrics.durations[&("GET".to_string(), "/api/times".to_string())];
+        assert_eq!(histogram.count, 3);
+        let cumulative = histogram.cumulative();
+        assert_eq!(cumulative[0], (0.005, 1));
+        assert_eq!(cumulative[6], (0.5, 2));
+        assert_eq!(cumulative.last(), Some(&(10.0, 2)));
+    }
+}
@@ -1,2 +1,18 @@
-mod task;
+pub mod analytics;
+pub mod backup;
+pub mod compare;
+pub mod dates;
+pub mod error;
+pub mod filter;
+pub mod history;
+pub mod import;
+pub mod metrics;
+pub mod pomodoro;
+pub mod report;
+pub mod rounding;
+pub mod schedule;
+pub mod snapshot;
+pub mod stats;
+pub mod task;
 pub mod taskmanager;
+pub mod timeline;
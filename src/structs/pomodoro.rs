@@ -0,0 +1,130 @@
+use super::error::TrackerError;
+use super::task::SecType;
+use serde::Serialize;
+
+/// longest work or break phase, a day.
+pub const MAX_PHASE_MINUTES: u64 = 24 * 60;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Work,
+    Break,
+}
+
+/// A timebox running on one activity.
+/// Work and break phases follow each other until the
+/// activity is stopped or another one is started.
+/// Without a break the pomodoro is a single timebox that
+/// stops the activity when the work phase runs out.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Pomodoro {
+    pub activity: String,
+    pub work_secs: SecType,
+    pub break_secs: SecType,
+    pub phase: Phase,
+    /// timestamp for when the current phase started.
+    pub phase_started: SecType,
+}
+
+impl Pomodoro {
+    pub fn new(activity: &str, work_secs: SecType, break_secs: SecType, t: SecType) -> Self {
+        Self {
+            activity: activity.to_string(),
+            work_secs,
+            break_secs,
+            phase: Phase::Work,
+            phase_started: t,
+        }
+    }
+
+    pub fn phase_end(&self) -> SecType {
+        match self.phase {
+            Phase::Work => self.phase_started + self.work_secs,
+            Phase::Break => self.phase_started + self.break_secs,
+        }
+    }
+
+    pub fn remaining_secs(&self, now: SecType) -> SecType {
+        self.phase_end().saturating_sub(now)
+    }
+
+    /// is there a next phase after the work phase or does
+    /// the activity simply stop.
+    pub fn has_break(&self) -> bool {
+        self.break_secs > 0
+    }
+
+    /// switches to the other phase, starting when the current one ends.
+    pub fn advance(&mut self) {
+        self.phase_started = self.phase_end();
+        self.phase = match self.phase {
+            Phase::Work => Phase::Break,
+            Phase::Break => Phase::Work,
+        };
+    }
+}
+
+/// the minutes of a phase as seconds.
+pub fn phase_secs(minutes: u64) -> Result<SecType, TrackerError> {
+    if minutes > MAX_PHASE_MINUTES {
+        return Err(TrackerError::InvalidValue(format!(
+            "phases can be at most {} minutes, got {}",
+            MAX_PHASE_MINUTES, minutes
+        )));
+    }
+    Ok(minutes * 60)
+}
+
+/// the minutes of a work phase as seconds. A work phase of 0 minutes
+/// would end on the next tick and count as a pomodoro, so it needs at
+/// least a minute.
+pub fn work_secs(minutes: u64) -> Result<SecType, TrackerError> {
+    if minutes == 0 {
+        return Err(TrackerError::InvalidValue(
+            "the work phase needs at least a minute".to_string(),
+        ));
+    }
+    phase_secs(minutes)
+}
+
+pub fn secs_to_minutes_seconds(secs: SecType) -> String {
+    format!("{:02}m:{:02}s", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn phases_follow_each_other() {
+        let mut pomodoro = Pomodoro::new("task", 25 * 60, 5 * 60, 1000);
+        assert_eq!(pomodoro.phase, Phase::Work);
+        assert_eq!(pomodoro.phase_end(), 1000 + 25 * 60);
+        assert_eq!(pomodoro.remaining_secs(1000 + 60), 24 * 60);
+        pomodoro.advance();
+        assert_eq!(pomodoro.phase, Phase::Break);
+        assert_eq!(pomodoro.phase_end(), 1000 + 30 * 60);
+        pomodoro.advance();
+        assert_eq!(pomodoro.phase, Phase::Work);
+        assert_eq!(pomodoro.phase_started, 1000 + 30 * 60);
+        assert_eq!(pomodoro.remaining_secs(1000 + 60 * 60), 0);
+    }
+
+    #[test]
+    fn pretty_remaining_time() {
+        assert_eq!(secs_to_minutes_seconds(25 * 60), "25m:00s");
+        assert_eq!(secs_to_minutes_seconds(61), "01m:01s");
+    }
+
+    #[test]
+    fn phases_are_bounded() {
+        assert_eq!(phase_secs(25).unwrap(), 25 * 60);
+        assert_eq!(phase_secs(MAX_PHASE_MINUTES).unwrap(), 24 * 60 * 60);
+        assert!(phase_secs(u64::MAX).is_err());
+        assert_eq!(phase_secs(0).unwrap(), 0);
+        assert!(work_secs(0).is_err());
+        assert_eq!(work_secs(1).unwrap(), 60);
+        assert!(work_secs(MAX_PHASE_MINUTES + 1).is_err());
+    }
+}
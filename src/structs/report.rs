@@ -0,0 +1,327 @@
+use super::dates::{check_range, day_start, next_day};
+use super::error::TrackerError;
+use super::rounding::Rounding;
+use super::task::*;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// name used for activities without a project.
+pub const NO_PROJECT: &str = "(no project)";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl std::str::FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            _ => Err(format!("unknown period: {}", s)),
+        }
+    }
+}
+
+impl Period {
+    /// first and last date of the period the date is in.
+    /// Weeks start on monday.
+    pub fn range(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            Period::Day => (date, date),
+            Period::Week => {
+                let first = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                (first, first + Duration::days(6))
+            }
+            Period::Month => {
+                let first = date.with_day(1).unwrap();
+                let next_month = if first.month() == 12 {
+                    NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+                } else {
+                    NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+                };
+                let last = next_month.map_or(NaiveDate::MAX, |next| next.pred_opt().unwrap());
+                (first, last)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ActivityTotal {
+    pub name: String,
+    pub project: Option<String>,
+    pub secs: SecType,
+    pub pretty: String,
+    pub billable_secs: SecType,
+    pub billable_pretty: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ProjectTotal {
+    pub project: String,
+    pub secs: SecType,
+    pub pretty: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct DayTotal {
+    pub date: String,
+    pub weekday: String,
+    /// seconds per activity name, only activities with time that day.
+    pub activities: BTreeMap<String, SecType>,
+    pub total_secs: SecType,
+    /// time when anything was running, this is compared to the target.
+    pub wall_clock_secs: SecType,
+    pub target_secs: SecType,
+    pub difference_secs: i64,
+}
+
+/// Time spent between two dates, both included.
+/// Intervals running over the edges of the range or of a day
+/// are cut at local midnight.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Report {
+    pub from: String,
+    pub to: String,
+    pub activities: Vec<ActivityTotal>,
+    pub projects: Vec<ProjectTotal>,
+    pub days: Vec<DayTotal>,
+    pub total_secs: SecType,
+    pub total_pretty: String,
+    pub wall_clock_secs: SecType,
+    pub target_secs: SecType,
+    pub target_pretty: String,
+    pub difference_secs: i64,
+    pub difference_pretty: String,
+    pub billable_secs: SecType,
+    pub billable_pretty: String,
+}
+
+impl Report {
+    /// `day_target` is the expected time for every weekday,
+    /// weekends have no target. The range is checked with check_range.
+    pub fn new(
+        tasks: &[Activity],
+        from: NaiveDate,
+        to: NaiveDate,
+        rounding: &Rounding,
+        day_target: SecType,
+    ) -> Result<Self, TrackerError> {
+        check_range(from, to)?;
+        let range_start = day_start(from);
+        let range_end = day_start(next_day(to)?);
+
+        let mut activities: Vec<ActivityTotal> = tasks
+            .iter()
+            .map(|task| {
+                let intervals = task.intervals_between(range_start, range_end);
+                let secs = intervals.iter().map(|i| i.secs()).sum();
+                let billable_secs = rounding.billable(&intervals);
+                ActivityTotal {
+                    name: task.name(),
+                    project: task.project(),
+                    secs,
+                    pretty: secs_to_pretty(secs),
+                    billable_secs,
+                    billable_pretty: secs_to_pretty(billable_secs),
+                }
+            })
+            .filter(|total| total.secs > 0)
+            .collect();
+        activities.sort_by(|a, b| b.secs.cmp(&a.secs).then(a.name.cmp(&b.name)));
+
+        let mut project_secs: BTreeMap<String, SecType> = BTreeMap::new();
+        for total in activities.iter() {
+            let project = total.project.as_deref().unwrap_or(NO_PROJECT).to_string();
+            *project_secs.entry(project).or_insert(0) += total.secs;
+        }
+        let projects = project_secs
+            .into_iter()
+            .map(|(project, secs)| ProjectTotal {
+                project,
+                secs,
+                pretty: secs_to_pretty(secs),
+            })
+            .collect();
+
+        let days: Vec<DayTotal> = from
+            .iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| day_total(tasks, date, day_target))
+            .collect();
+
+        let total_secs = activities.iter().map(|a| a.secs).sum();
+        let wall_clock_secs = days.iter().map(|d| d.wall_clock_secs).sum();
+        let target_secs = days.iter().map(|d| d.target_secs).sum();
+        let difference_secs = wall_clock_secs as i64 - target_secs as i64;
+        let billable_secs = activities.iter().map(|a| a.billable_secs).sum();
+        Ok(Report {
+            from: from.format("%Y-%m-%d").to_string(),
+            to: to.format("%Y-%m-%d").to_string(),
+            activities,
+            projects,
+            days,
+            total_secs,
+            total_pretty: secs_to_pretty(total_secs),
+            wall_clock_secs,
+            target_secs,
+            target_pretty: secs_to_pretty(target_secs),
+            difference_secs,
+            difference_pretty: signed_secs_to_pretty(difference_secs),
+            billable_secs,
+            billable_pretty: secs_to_pretty(billable_secs),
+        })
+    }
+
+    /// The report as a text table, formatted like the pretty endpoint.
+    pub fn text(&self) -> String {
+        let mut result = format!("report:             {} - {}", self.from, self.to);
+        result.push_str(&format!("\ntotal acivity time: {}", self.total_pretty));
+        result.push_str(&format!("\ntarget time:        {}", self.target_pretty));
+        result.push_str(&format!("\ndifference:         {}", self.difference_pretty));
+        if self.billable_secs != self.total_secs {
+            result.push_str(&format!("\nbillable time:      {}", self.billable_pretty));
+        }
+        result.push_str("\n\nactivities:");
+        for total in self.activities.iter() {
+            result.push_str(&format!("\n  {}", name_time_text(&total.name, total.secs)));
+        }
+        result.push_str("\n\nprojects:");
+        for total in self.projects.iter() {
+            result.push_str(&format!(
+                "\n  {}",
+                name_time_text(&total.project, total.secs)
+            ));
+        }
+        result.push_str("\n\ndays:");
+        for day in self.days.iter() {
+            result.push_str(&format!(
+                "\n  {} {}: {} of {} ({})",
+                day.date,
+                day.weekday,
+                secs_to_pretty(day.wall_clock_secs),
+                secs_to_pretty(day.target_secs),
+                signed_secs_to_pretty(day.difference_secs)
+            ));
+        }
+        result.push('\n');
+        result
+    }
+}
+
+fn day_total(tasks: &[Activity], date: NaiveDate, day_target: SecType) -> DayTotal {
+    let start = day_start(date);
+    let end = day_start(date.succ_opt().unwrap());
+    let mut activities = BTreeMap::new();
+    let mut intervals = Vec::new();
+    for task in tasks {
+        let task_intervals = task.intervals_between(start, end);
+        let secs: SecType = task_intervals.iter().map(|i| i.secs()).sum();
+        if secs > 0 {
+            activities.insert(task.name(), secs);
+        }
+        intervals.extend(task_intervals);
+    }
+    let wall_clock_secs = wall_clock_secs(&intervals);
+    let target_secs = match date.weekday() {
+        Weekday::Sat | Weekday::Sun => 0,
+        _ => day_target,
+    };
+    DayTotal {
+        date: date.format("%Y-%m-%d").to_string(),
+        weekday: date.format("%a").to_string(),
+        total_secs: activities.values().sum(),
+        activities,
+        wall_clock_secs,
+        target_secs,
+        difference_secs: wall_clock_secs as i64 - target_secs as i64,
+    }
+}
+
+pub fn signed_secs_to_pretty(secs: i64) -> String {
+    let sign = if secs < 0 { "-" } else { "+" };
+    format!("{}{}", sign, secs_to_pretty(secs.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn period_ranges() {
+        let thursday = date("2026-10-15");
+        assert_eq!(Period::Day.range(thursday), (thursday, thursday));
+        assert_eq!(
+            Period::Week.range(thursday),
+            (date("2026-10-12"), date("2026-10-18"))
+        );
+        assert_eq!(
+            Period::Month.range(thursday),
+            (date("2026-10-01"), date("2026-10-31"))
+        );
+        assert_eq!(
+            Period::Month.range(date("2026-12-24")),
+            (date("2026-12-01"), date("2026-12-31"))
+        );
+        assert_eq!(
+            Period::Month.range(date("2028-02-10")),
+            (date("2028-02-01"), date("2028-02-29"))
+        );
+    }
+
+    #[test]
+    fn report_totals() {
+        let monday = date("2026-10-12");
+        let mut alpha = Activity::new("alpha");
+        alpha.set_project("billing");
+        alpha.stop_at(0);
+        let nine = day_start(monday) + 9 * 60 * 60;
+        alpha.start_at(nine);
+        alpha.stop_at(nine + 60 * 60);
+        let mut beta = Activity::new("beta");
+        beta.stop_at(0);
+        beta.start_at(nine + 60 * 60);
+        beta.stop_at(nine + 90 * 60);
+        beta.start_at(day_start(date("2026-10-20")));
+        beta.stop_at(day_start(date("2026-10-20")) + 60);
+
+        let report = Report::new(
+            &[alpha, beta],
+            monday,
+            date("2026-10-18"),
+            &Rounding::default(),
+            60 * 60,
+        )
+        .unwrap();
+        assert_eq!(report.total_secs, 90 * 60);
+        assert_eq!(report.activities[0].name, "alpha");
+        assert_eq!(report.activities[1].secs, 30 * 60);
+        assert_eq!(report.projects[0].project, NO_PROJECT);
+        assert_eq!(report.projects[1].project, "billing");
+        assert_eq!(report.days.len(), 7);
+        assert_eq!(report.days[0].activities["beta"], 30 * 60);
+        assert_eq!(report.days[0].difference_secs, 30 * 60);
+        assert_eq!(report.days[5].target_secs, 0);
+        assert_eq!(report.target_secs, 5 * 60 * 60);
+        assert_eq!(report.difference_pretty, "-03h:30m");
+        assert!(report
+            .text()
+            .contains("2026-10-12 Mon: 01h:30m of 01h:00m (+00h:30m)"));
+
+        let rounding = Rounding::default();
+        assert!(Report::new(&[], date("2026-10-18"), monday, &rounding, 0).is_err());
+        assert!(Report::new(&[], date("0001-01-01"), date("9999-12-31"), &rounding, 0).is_err());
+        assert!(Report::new(&[], NaiveDate::MAX, NaiveDate::MAX, &rounding, 0).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use super::dates::local_date;
+use super::task::{Activity, Interval, SecType};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// the largest increment or minimum, in minutes.
+pub const MAX_MINUTES: u64 = 24 * 60;
+
+/// Which way a duration is moved to the nearest increment.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    Up,
+    Down,
+    Nearest,
+}
+
+/// What gets rounded: every interval on its own, the sum of
+/// each day or only the total of the whole report.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingScope {
+    Interval,
+    Day,
+    Report,
+}
+
+/// Billing rules applied when time is reported.
+/// The stored intervals are never changed, rounding only
+/// happens on the way out.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Rounding {
+    /// size of the billing increment. 0 turns rounding off.
+    pub increment_secs: SecType,
+    pub mode: RoundingMode,
+    pub scope: RoundingScope,
+    /// any non zero rounded time is raised to at least this much.
+    pub minimum_secs: SecType,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self {
+            increment_secs: 0,
+            mode: RoundingMode::Nearest,
+            scope: RoundingScope::Report,
+            minimum_secs: 0,
+        }
+    }
+}
+
+impl std::str::FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(RoundingMode::Up),
+            "down" => Ok(RoundingMode::Down),
+            "nearest" => Ok(RoundingMode::Nearest),
+            _ => Err(format!("unknown rounding mode: {}", s)),
+        }
+    }
+}
+
+impl std::str::FromStr for RoundingScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "interval" => Ok(RoundingScope::Interval),
+            "day" => Ok(RoundingScope::Day),
+            "report" => Ok(RoundingScope::Report),
+            _ => Err(format!("unknown rounding scope: {}", s)),
+        }
+    }
+}
+
+impl Rounding {
+    pub fn is_enabled(&self) -> bool {
+        self.increment_secs > 0 || self.minimum_secs > 0
+    }
+
+    /// rounds a single duration. Zero stays zero so
+    /// untouched activities are never billed.
+    pub fn round(&self, secs: SecType) -> SecType {
+        if secs == 0 {
+            return 0;
+        }
+        let increment = self.increment_secs;
+        let rounded = if increment == 0 {
+            secs
+        } else {
+            let count = match self.mode {
+                RoundingMode::Up => secs.div_ceil(increment),
+                RoundingMode::Down => secs / increment,
+                RoundingMode::Nearest => (secs + increment / 2) / increment,
+            };
+            count * increment
+        };
+        rounded.max(self.minimum_secs)
+    }
+
+    /// billable time of a set of intervals according to the scope.
+    /// Day scope groups the intervals by the local date they started on.
+    pub fn billable(&self, intervals: &[Interval]) -> SecType {
+        match self.scope {
+            RoundingScope::Interval => intervals.iter().map(|i| self.round(i.secs())).sum(),
+            RoundingScope::Day => {
+                let mut days: BTreeMap<NaiveDate, SecType> = BTreeMap::new();
+                for interval in intervals {
+                    *days.entry(local_date(interval.start)).or_insert(0) += interval.secs();
+                }
+                days.values().map(|secs| self.round(*secs)).sum()
+            }
+            RoundingScope::Report => self.round(intervals.iter().map(|i| i.secs()).sum()),
+        }
+    }
+
+    /// billable time of everything an activity logged. Time from before
+    /// intervals were kept has no date, it is rounded as one more piece.
+    /// Reports of a date range only see the intervals.
+    pub fn billable_activity(&self, task: &Activity) -> SecType {
+        let intervals = task.intervals();
+        let untimed = task.untimed_secs();
+        match self.scope {
+            RoundingScope::Report => {
+                self.round(intervals.iter().map(|i| i.secs()).sum::<SecType>() + untimed)
+            }
+            _ => self.billable(&intervals) + self.round(untimed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rounding(mode: RoundingMode, scope: RoundingScope) -> Rounding {
+        Rounding {
+            increment_secs: 15 * 60,
+            mode,
+            scope,
+            minimum_secs: 0,
+        }
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let up = rounding(RoundingMode::Up, RoundingScope::Report);
+        let down = rounding(RoundingMode::Down, RoundingScope::Report);
+        let nearest = rounding(RoundingMode::Nearest, RoundingScope::Report);
+        assert_eq!(up.round(0), 0);
+        assert_eq!(up.round(1), 15 * 60);
+        assert_eq!(up.round(15 * 60), 15 * 60);
+        assert_eq!(down.round(29 * 60), 15 * 60);
+        assert_eq!(nearest.round(7 * 60), 0);
+        assert_eq!(nearest.round(8 * 60), 15 * 60);
+        assert_eq!(Rounding::default().round(61), 61);
+    }
+
+    #[test]
+    fn minimum_billable_unit() {
+        let mut rounding = rounding(RoundingMode::Nearest, RoundingScope::Report);
+        rounding.minimum_secs = 30 * 60;
+        assert_eq!(rounding.round(60), 30 * 60);
+        assert_eq!(rounding.round(50 * 60), 45 * 60);
+        assert_eq!(rounding.round(0), 0);
+    }
+
+    #[test]
+    fn rounding_scopes() {
+        let day = 24 * 60 * 60;
+        let intervals = vec![
+            Interval { start: 0, end: 60 },
+            Interval {
+                start: 120,
+                end: 180,
+            },
+            Interval {
+                start: 2 * day,
+                end: 2 * day + 60,
+            },
+        ];
+        let per_interval = rounding(RoundingMode::Up, RoundingScope::Interval);
+        let per_day = rounding(RoundingMode::Up, RoundingScope::Day);
+        let per_report = rounding(RoundingMode::Up, RoundingScope::Report);
+        assert_eq!(per_interval.billable(&intervals), 3 * 15 * 60);
+        assert_eq!(per_day.billable(&intervals), 2 * 15 * 60);
+        assert_eq!(per_report.billable(&intervals), 15 * 60);
+    }
+
+    #[test]
+    fn time_without_intervals_is_billed() {
+        let legacy: Activity = serde_json::from_str(
+            r#"{"added_at": "", "name": "old", "status": "Idle", "logged_secs": 600}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.untimed_secs(), 600);
+        let per_report = rounding(RoundingMode::Up, RoundingScope::Report);
+        let per_interval = rounding(RoundingMode::Up, RoundingScope::Interval);
+        assert_eq!(per_report.billable_activity(&legacy), 15 * 60);
+        assert_eq!(per_interval.billable_activity(&legacy), 15 * 60);
+        assert_eq!(per_report.billable(&legacy.intervals()), 0);
+    }
+}
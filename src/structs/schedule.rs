@@ -0,0 +1,214 @@
+use super::error::TrackerError;
+use super::task::{normalize_name, SecType};
+use chrono::{Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// longest timed start, a day.
+pub const MAX_MINUTES: u64 = 24 * 60;
+
+/// What a schedule does when it's due.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum Action {
+    /// stops every running activity.
+    Stop,
+    /// starts an activity. With minutes the activity that ran
+    /// before is resumed when the time is up, or nothing if no
+    /// activity was running.
+    Start {
+        name: String,
+        #[serde(default)]
+        minutes: Option<u64>,
+    },
+}
+
+/// An action that runs at a local time of day, once or every day.
+/// Schedules are stored next to the db file so they survive restarts.
+/// Runs missed while the server was down are caught up with the
+/// action backdated to the time it was due.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: u64,
+    /// local time of day as HH:MM.
+    pub at: String,
+    #[serde(flatten)]
+    pub action: Action,
+    /// runs every day, otherwise it is removed after running once.
+    #[serde(default)]
+    pub daily: bool,
+    /// timestamp for when the schedule was added.
+    pub created: SecType,
+    /// timestamp of the latest occurrence that has run.
+    #[serde(default)]
+    pub last_run: Option<SecType>,
+    /// when a timed start ends.
+    #[serde(default)]
+    pub return_at: Option<SecType>,
+    /// the activity to go back to when the timed start ends.
+    #[serde(default)]
+    pub return_to: Option<String>,
+}
+
+impl Schedule {
+    pub fn new(
+        id: u64,
+        at: &str,
+        action: Action,
+        daily: bool,
+        created: SecType,
+    ) -> Result<Self, TrackerError> {
+        let schedule = Self {
+            id,
+            at: at.trim().to_string(),
+            action,
+            daily,
+            created,
+            last_run: None,
+            return_at: None,
+            return_to: None,
+        };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// checks the time of day, that a start names an activity the
+    /// way normalize_name allows and that a timed start ends within
+    /// a day.
+    pub fn validate(&self) -> Result<(), TrackerError> {
+        parse_time_of_day(&self.at)?;
+        if let Action::Start { name, minutes } = &self.action {
+            normalize_name(name)?;
+            if minutes.is_some_and(|minutes| minutes > MAX_MINUTES) {
+                return Err(TrackerError::InvalidValue(format!(
+                    "a timed start can be at most {} minutes",
+                    MAX_MINUTES
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// follows an activity that got a new name.
+    pub fn rename(&mut self, old: &str, new: &str) {
+        if let Action::Start { name, .. } = &mut self.action {
+            if name == old {
+                *name = new.to_string();
+            }
+        }
+        if self.return_to.as_deref() == Some(old) {
+            self.return_to = Some(new.to_string());
+        }
+    }
+
+    /// lets go of a deleted activity, false when the schedule
+    /// starts it and has nothing left to do.
+    pub fn forget(&mut self, old: &str) -> bool {
+        if self.return_to.as_deref() == Some(old) {
+            self.return_to = None;
+        }
+        !matches!(&self.action, Action::Start { name, .. } if name == old)
+    }
+
+    /// the latest occurrence up to now that hasn't run yet.
+    /// Older missed occurrences are skipped, only the latest runs.
+    pub fn due(&self, now: SecType) -> Option<SecType> {
+        if !self.daily && self.last_run.is_some() {
+            return None;
+        }
+        let time = parse_time_of_day(&self.at).ok()?;
+        let today = Local
+            .timestamp_opt(now as i64, 0)
+            .unwrap()
+            .naive_local()
+            .date();
+        let occurrence = [today, today - Duration::days(1)]
+            .iter()
+            .filter_map(|date| occurrence_on(*date, time))
+            .find(|t| *t <= now)?;
+        let reference = self.last_run.unwrap_or(self.created);
+        if occurrence > reference {
+            Some(occurrence)
+        } else {
+            None
+        }
+    }
+
+    /// a one time schedule is done once it ran and its timed start ended.
+    pub fn is_finished(&self) -> bool {
+        !self.daily && self.last_run.is_some() && self.return_at.is_none()
+    }
+}
+
+fn parse_time_of_day(text: &str) -> Result<NaiveTime, TrackerError> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M")
+        .map_err(|_| TrackerError::InvalidTime(format!("expected HH:MM, got: {}", text)))
+}
+
+fn occurrence_on(date: NaiveDate, time: NaiveTime) -> Option<SecType> {
+    Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|datetime| datetime.timestamp() as SecType)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::task::MAX_NAME_CHARS;
+
+    fn local(date: &str, time: &str) -> SecType {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        occurrence_on(date, parse_time_of_day(time).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn invalid_schedules() {
+        assert!(Schedule::new(1, "25:00", Action::Stop, true, 0).is_err());
+        let start = Action::Start {
+            name: " ".to_string(),
+            minutes: None,
+        };
+        assert!(Schedule::new(1, "09:30", start, true, 0).is_err());
+        for name in ["bell\u{7}".to_string(), "x".repeat(MAX_NAME_CHARS + 1)] {
+            let start = Action::Start {
+                name,
+                minutes: None,
+            };
+            assert!(Schedule::new(1, "09:30", start, true, 0).is_err());
+        }
+        let endless = Action::Start {
+            name: "standup".to_string(),
+            minutes: Some(u64::MAX),
+        };
+        assert!(Schedule::new(1, "09:30", endless, true, 0).is_err());
+    }
+
+    #[test]
+    fn daily_schedule_is_due_once_a_day() {
+        let created = local("2026-10-18", "12:00");
+        let mut schedule = Schedule::new(1, "18:00", Action::Stop, true, created).unwrap();
+        assert_eq!(schedule.due(local("2026-10-18", "17:59")), None);
+        let due = local("2026-10-18", "18:00");
+        assert_eq!(schedule.due(local("2026-10-18", "18:05")), Some(due));
+        schedule.last_run = Some(due);
+        assert_eq!(schedule.due(local("2026-10-18", "23:00")), None);
+        assert_eq!(schedule.due(local("2026-10-19", "09:00")), None);
+        assert_eq!(
+            schedule.due(local("2026-10-19", "20:00")),
+            Some(local("2026-10-19", "18:00"))
+        );
+        assert!(!schedule.is_finished());
+    }
+
+    #[test]
+    fn one_time_schedule_runs_once() {
+        let created = local("2026-10-18", "19:00");
+        let mut schedule = Schedule::new(1, "18:00", Action::Stop, false, created).unwrap();
+        assert_eq!(schedule.due(local("2026-10-18", "20:00")), None);
+        let due = local("2026-10-19", "18:00");
+        assert_eq!(schedule.due(local("2026-10-20", "09:00")), Some(due));
+        schedule.last_run = Some(due);
+        assert_eq!(schedule.due(local("2026-10-21", "09:00")), None);
+        assert!(schedule.is_finished());
+    }
+}
@@ -0,0 +1,158 @@
+use super::backup::{write_atomic, Backup};
+use super::dates::local_pretty;
+use super::task::SecType;
+use chrono::{NaiveDateTime, TimeZone, Utc};
+
+const NAME_FORMAT: &str = "%Y%m%dT%H%M%SZ.json";
+
+/// How often snapshots are taken.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Every {
+    Hour,
+    Day,
+}
+
+impl std::str::FromStr for Every {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hourly" => Ok(Every::Hour),
+            "daily" => Ok(Every::Day),
+            _ => Err(format!("snapshots can be hourly or daily, got: {}", s)),
+        }
+    }
+}
+
+impl Every {
+    pub fn secs(&self) -> SecType {
+        match self {
+            Every::Hour => 60 * 60,
+            Every::Day => 24 * 60 * 60,
+        }
+    }
+}
+
+/// Snapshots are taken every hour or day and the newest `keep`
+/// of them are kept.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SnapshotPolicy {
+    pub every: Every,
+    pub keep: usize,
+}
+
+/// A backup archive in the snapshot directory, named after the
+/// time it was taken.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Snapshot {
+    pub path: std::path::PathBuf,
+    pub created: SecType,
+}
+
+impl Snapshot {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn read(&self) -> Result<Backup, String> {
+        let text = std::fs::read_to_string(&self.path).map_err(|error| error.to_string())?;
+        serde_json::from_str(&text).map_err(|error| format!("{}: {}", self.name(), error))
+    }
+
+    /// one line for listings.
+    pub fn text(&self) -> String {
+        format!("{}  {}", self.name(), local_pretty(self.created))
+    }
+}
+
+/// the snapshots in `dir`, oldest first. Other files are left out.
+pub fn list(dir: &std::path::Path) -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let created = NaiveDateTime::parse_from_str(&name, NAME_FORMAT).ok()?;
+                Some(Snapshot {
+                    path: entry.path(),
+                    created: SecType::try_from(created.timestamp()).ok()?,
+                })
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    snapshots.sort_by_key(|snapshot| snapshot.created);
+    snapshots
+}
+
+/// writes a snapshot taken at `now` and removes the oldest ones
+/// past `keep`.
+pub fn take(
+    dir: &std::path::Path,
+    backup: &Backup,
+    now: SecType,
+    keep: usize,
+) -> std::io::Result<Snapshot> {
+    std::fs::create_dir_all(dir)?;
+    let name = Utc
+        .timestamp_opt(now as i64, 0)
+        .unwrap()
+        .format(NAME_FORMAT)
+        .to_string();
+    let snapshot = Snapshot {
+        path: dir.join(name),
+        created: now,
+    };
+    write_atomic(
+        &snapshot.path,
+        &serde_json::to_string_pretty(backup).unwrap(),
+    )?;
+    let snapshots = list(dir);
+    let surplus = snapshots.len().saturating_sub(keep.max(1));
+    for old in snapshots.iter().take(surplus) {
+        std::fs::remove_file(&old.path)?;
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::backup::{Settings, BACKUP_VERSION};
+    use crate::structs::rounding::Rounding;
+
+    #[test]
+    fn taking_and_pruning() {
+        let dir = &std::env::temp_dir().join(format!(
+            "timetracker-{}-taking_and_pruning",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(dir);
+        let backup = Backup {
+            version: BACKUP_VERSION,
+            created: 0,
+            settings: Settings {
+                rounding: Rounding::default(),
+                concurrent: false,
+            },
+            activities: Vec::new(),
+            schedules: Vec::new(),
+        };
+        let start = 1_790_000_000;
+        for hour in 0..4 {
+            take(dir, &backup, start + hour * 3600, 3).unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a snapshot").unwrap();
+        let snapshots = list(dir);
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots[0].created, start + 3600);
+        assert_eq!(snapshots[2].read().unwrap(), backup);
+        assert!(snapshots[0].name().ends_with("Z.json"));
+        assert!(list(std::path::Path::new("no such dir")).is_empty());
+        assert_eq!("daily".parse::<Every>(), Ok(Every::Day));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
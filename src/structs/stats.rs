@@ -0,0 +1,173 @@
+use super::report::{Report, NO_PROJECT};
+use super::task::SecType;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Daily totals per activity for a stacked bar chart.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct DailyStats {
+    pub from: String,
+    pub to: String,
+    /// every activity with time in the range, most time first,
+    /// so the series keep their order and colour between days.
+    pub activities: Vec<String>,
+    pub days: Vec<DayStats>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct DayStats {
+    pub date: String,
+    pub weekday: String,
+    pub activities: BTreeMap<String, SecType>,
+    pub total_secs: SecType,
+    pub target_secs: SecType,
+}
+
+impl DailyStats {
+    pub fn new(report: &Report) -> Self {
+        Self {
+            from: report.from.to_owned(),
+            to: report.to.to_owned(),
+            activities: report
+                .activities
+                .iter()
+                .map(|a| a.name.to_owned())
+                .collect(),
+            days: report
+                .days
+                .iter()
+                .map(|day| DayStats {
+                    date: day.date.to_owned(),
+                    weekday: day.weekday.to_owned(),
+                    activities: day.activities.to_owned(),
+                    total_secs: day.total_secs,
+                    target_secs: day.target_secs,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Group {
+    Activity,
+    Project,
+}
+
+impl std::str::FromStr for Group {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "activity" => Ok(Group::Activity),
+            "project" => Ok(Group::Project),
+            _ => Err(format!("group can be activity or project, got: {}", s)),
+        }
+    }
+}
+
+/// Shares of the whole range for a pie chart or treemap.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Breakdown {
+    pub from: String,
+    pub to: String,
+    pub total_secs: SecType,
+    /// most time first.
+    pub items: Vec<Share>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Share {
+    pub name: String,
+    pub secs: SecType,
+    /// part of the total between 0 and 1.
+    pub share: f64,
+}
+
+impl Breakdown {
+    pub fn new(report: &Report, group: Group) -> Self {
+        let mut totals: BTreeMap<String, SecType> = BTreeMap::new();
+        for activity in report.activities.iter() {
+            let name = match group {
+                Group::Activity => activity.name.to_owned(),
+                Group::Project => activity
+                    .project
+                    .to_owned()
+                    .unwrap_or_else(|| NO_PROJECT.to_string()),
+            };
+            *totals.entry(name).or_insert(0) += activity.secs;
+        }
+        let total_secs: SecType = totals.values().sum();
+        let mut items: Vec<Share> = totals
+            .into_iter()
+            .map(|(name, secs)| Share {
+                name,
+                secs,
+                share: if total_secs == 0 {
+                    0.0
+                } else {
+                    secs as f64 / total_secs as f64
+                },
+            })
+            .collect();
+        items.sort_by(|a, b| b.secs.cmp(&a.secs).then(a.name.cmp(&b.name)));
+        Self {
+            from: report.from.to_owned(),
+            to: report.to.to_owned(),
+            total_secs,
+            items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::{day_start, parse_date};
+    use crate::structs::rounding::Rounding;
+    use crate::structs::task::Activity;
+
+    #[test]
+    fn daily_stats_and_breakdowns() {
+        let monday = parse_date("2026-10-12").unwrap();
+        let tuesday = monday.succ_opt().unwrap();
+        let mut alpha = Activity::new("alpha");
+        alpha.set_project("billing");
+        alpha.stop_at(0);
+        alpha.start_at(day_start(monday) + 3600);
+        alpha.stop_at(day_start(monday) + 3 * 3600);
+        let mut beta = Activity::new("beta");
+        beta.set_project("billing");
+        beta.stop_at(0);
+        beta.start_at(day_start(tuesday) + 3600);
+        beta.stop_at(day_start(tuesday) + 2 * 3600);
+        let mut gamma = Activity::new("gamma");
+        gamma.stop_at(0);
+        gamma.start_at(day_start(tuesday) + 2 * 3600);
+        gamma.stop_at(day_start(tuesday) + 3 * 3600);
+        let report = Report::new(
+            &[gamma, beta, alpha],
+            monday,
+            tuesday,
+            &Rounding::default(),
+            0,
+        )
+        .unwrap();
+
+        let daily = DailyStats::new(&report);
+        assert_eq!(daily.activities, ["alpha", "beta", "gamma"]);
+        assert_eq!(daily.days.len(), 2);
+        assert_eq!(daily.days[1].activities["gamma"], 3600);
+        assert_eq!(daily.days[1].total_secs, 2 * 3600);
+
+        let activities = Breakdown::new(&report, Group::Activity);
+        assert_eq!(activities.total_secs, 4 * 3600);
+        assert_eq!(activities.items[0].name, "alpha");
+        assert_eq!(activities.items[0].share, 0.5);
+        let projects = Breakdown::new(&report, Group::Project);
+        assert_eq!(projects.items.len(), 2);
+        assert_eq!(projects.items[0].name, "billing");
+        assert_eq!(projects.items[1].name, NO_PROJECT);
+        assert_eq!(projects.items[1].share, 0.25);
+    }
+}
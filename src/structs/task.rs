@@ -1,3 +1,4 @@
+use super::error::TrackerError;
 use chrono::Local;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
@@ -5,12 +6,67 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub type SecType = u64;
 
+/// longest activity name, in characters.
+pub const MAX_NAME_CHARS: usize = 100;
+
+/// An activity name as it is stored: without leading or trailing
+/// whitespace and with inner whitespace as single spaces. Empty names,
+/// names longer than MAX_NAME_CHARS and control characters are refused.
+pub fn normalize_name(text: &str) -> Result<String, TrackerError> {
+    if text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+        return Err(TrackerError::InvalidValue(format!(
+            "names can't contain control characters: {:?}",
+            text
+        )));
+    }
+    let name = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err(TrackerError::InvalidValue(
+            "an activity needs a name".to_string(),
+        ));
+    }
+    let length = name.chars().count();
+    if length > MAX_NAME_CHARS {
+        return Err(TrackerError::InvalidValue(format!(
+            "names can have at most {} characters, got {}",
+            MAX_NAME_CHARS, length
+        )));
+    }
+    Ok(name)
+}
+
+/// the stored name `text` refers to. Names stored by earlier versions
+/// can have extra whitespace or more than MAX_NAME_CHARS characters, so
+/// a stored name that is the same text or the same after normalizing
+/// wins over normalizing `text`.
+pub fn resolve_name(text: &str, stored: &[String]) -> Result<String, TrackerError> {
+    if let Some(name) = stored.iter().find(|name| *name == text) {
+        return Ok(name.to_owned());
+    }
+    if let Some(name) = stored.iter().find(|name| same_name(name, text)) {
+        return Ok(name.to_owned());
+    }
+    normalize_name(text)
+}
+
+/// the names only differ in whitespace.
+pub fn same_name(a: &str, b: &str) -> bool {
+    a.split_whitespace().eq(b.split_whitespace())
+}
+
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Status {
     ActiveSince(SecType),
     Idle,
 }
 
+/// A finished stretch of time the activity was running for.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Interval {
+    pub start: SecType,
+    pub end: SecType,
+}
+
 #[derive(Debug, PartialEq, Clone, Deserialize)]
 pub struct Activity {
     /// timestamp for when the activity is created
@@ -24,6 +80,43 @@ pub struct Activity {
     /// is added here.
     logged_secs: SecType,
     name: String,
+    /// stable number of the activity in the rest api. 0 until the
+    /// task manager numbers it.
+    #[serde(default)]
+    id: u64,
+    /// every start - stop pair, oldest first. The sum of these
+    /// is the same as logged_secs, they are kept for reports
+    /// that need to know when the time was spent.
+    #[serde(default)]
+    intervals: Vec<Interval>,
+    /// optional grouping of activities in reports.
+    #[serde(default)]
+    project: Option<String>,
+    /// free form labels, used for filtering and in exports.
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    notes: Option<String>,
+    /// number of finished pomodoro work phases.
+    #[serde(default)]
+    pomodoros: u32,
+    /// concurrent activities run next to others, starting
+    /// them does not stop anything and they are not stopped
+    /// when an other activity starts.
+    #[serde(default)]
+    concurrent: bool,
+}
+
+/// Details of an activity to set, the ones that are None stay as they
+/// are. Empty project or notes remove them.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActivityChanges {
+    pub name: Option<String>,
+    pub project: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub notes: Option<String>,
+    pub concurrent: Option<bool>,
 }
 
 impl Status {
@@ -39,6 +132,29 @@ impl Status {
     }
 }
 
+impl Interval {
+    pub fn secs(&self) -> SecType {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// the part of the interval inside the `start` - `end` range, if any.
+    pub fn clip(&self, start: SecType, end: SecType) -> Option<Interval> {
+        let clipped = Interval {
+            start: self.start.max(start),
+            end: self.end.min(end),
+        };
+        if clipped.start < clipped.end {
+            Some(clipped)
+        } else {
+            None
+        }
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
 impl Serialize for Activity {
     /// The custom serialiser adds fields that are not worth storing.
     /// Those fields are calculated on demand from stored
@@ -54,6 +170,13 @@ impl Serialize for Activity {
         state.serialize_field("status", &self.status)?;
         state.serialize_field("logged_secs", &self.logged_secs)?;
         state.serialize_field("name", &self.name)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("intervals", &self.intervals)?;
+        state.serialize_field("project", &self.project)?;
+        state.serialize_field("tags", &self.tags)?;
+        state.serialize_field("notes", &self.notes)?;
+        state.serialize_field("pomodoros", &self.pomodoros)?;
+        state.serialize_field("concurrent", &self.concurrent)?;
         let (hours, mins) = secs_to_hours_minutes(self.secs_since_creation());
         let all_time_pretty = format!("{}h:{:02}m", hours, mins);
         state.serialize_field("all_time_pretty", &all_time_pretty)?;
@@ -68,6 +191,13 @@ impl Activity {
             status: Status::ActiveSince(sys_now_secs()),
             logged_secs: 0,
             name: name.to_string(),
+            id: 0,
+            intervals: Vec::new(),
+            project: None,
+            tags: Vec::new(),
+            notes: None,
+            pomodoros: 0,
+            concurrent: false,
         }
     }
 
@@ -75,30 +205,230 @@ impl Activity {
         self.name.to_owned()
     }
 
-    pub fn start(&mut self) {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: u64) {
+        self.id = id;
+    }
+
+    /// the details in `changes`, the name included. Normalizing the
+    /// name and checking that it is free is up to the caller.
+    pub fn apply(&mut self, changes: &ActivityChanges) {
+        if let Some(name) = &changes.name {
+            self.name = name.to_owned();
+        }
+        if let Some(project) = &changes.project {
+            self.set_project(project);
+        }
+        if let Some(tags) = &changes.tags {
+            self.set_tags(tags);
+        }
+        if let Some(notes) = &changes.notes {
+            self.set_notes(notes);
+        }
+        if let Some(concurrent) = changes.concurrent {
+            self.concurrent = concurrent;
+        }
+    }
+
+    pub fn start_at(&mut self, t: SecType) {
         // on starting an activity the latest elapsed secs
         // are added to the logged time. This needs to be
         // done becouse the new stored status active since value.
         // If this is not added and an active task is
         // activated again the start time stamp will change,
         // but the logged time remains the same!
-        self.logged_secs += self.status.as_elapsed_secs();
-        self.status = Status::ActiveSince(sys_now_secs());
+        self.log_until(t);
+        self.status = Status::ActiveSince(t);
     }
 
-    pub fn stop(&mut self) {
-        self.logged_secs += self.status.as_elapsed_secs();
+    pub fn stop_at(&mut self, t: SecType) {
+        self.log_until(t);
         self.status = Status::Idle;
     }
 
+    pub fn add_pomodoro(&mut self) {
+        self.pomodoros += 1;
+    }
+
+    pub fn pomodoros(&self) -> u32 {
+        self.pomodoros
+    }
+
+    pub fn project(&self) -> Option<String> {
+        self.project.to_owned()
+    }
+
+    /// an empty project removes the activity from its project.
+    pub fn set_project(&mut self, project: &str) {
+        let project = project.trim();
+        self.project = if project.is_empty() {
+            None
+        } else {
+            Some(project.to_string())
+        };
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        self.tags.to_owned()
+    }
+
+    /// empty tags are dropped and every tag is kept only once.
+    pub fn set_tags(&mut self, tags: &[String]) {
+        self.tags.clear();
+        for tag in tags.iter().map(|tag| tag.trim()) {
+            if !tag.is_empty() && !self.tags.iter().any(|t| t == tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
+    }
+
+    pub fn notes(&self) -> Option<String> {
+        self.notes.to_owned()
+    }
+
+    /// empty notes remove the notes.
+    pub fn set_notes(&mut self, notes: &str) {
+        let notes = notes.trim();
+        self.notes = if notes.is_empty() {
+            None
+        } else {
+            Some(notes.to_string())
+        };
+    }
+
+    /// the recorded and running intervals cut to the `start` - `end` range.
+    pub fn intervals_between(&self, start: SecType, end: SecType) -> Vec<Interval> {
+        self.intervals()
+            .iter()
+            .filter_map(|i| i.clip(start, end))
+            .collect()
+    }
+
+    /// timestamp of the latest start or stop.
+    pub fn last_change(&self) -> SecType {
+        match self.status {
+            Status::ActiveSince(since) => since,
+            Status::Idle => self.intervals.last().map(|i| i.end).unwrap_or(0),
+        }
+    }
+
+    pub fn is_concurrent(&self) -> bool {
+        self.concurrent
+    }
+
+    pub fn set_concurrent(&mut self, concurrent: bool) {
+        self.concurrent = concurrent;
+    }
+
+    /// records time that was not tracked when it was spent. It can't
+    /// overlap the recorded intervals or the running one.
+    pub fn add_interval(&mut self, interval: Interval) -> Result<(), TrackerError> {
+        if interval.start >= interval.end {
+            return Err(TrackerError::InvalidTime(
+                "an interval has to end after it starts".to_string(),
+            ));
+        }
+        if self.intervals().iter().any(|i| i.overlaps(&interval)) {
+            return Err(TrackerError::InvalidTime(format!(
+                "{} already has time tracked in that interval",
+                self.name
+            )));
+        }
+        let position = self
+            .intervals
+            .iter()
+            .position(|i| i.start > interval.start)
+            .unwrap_or(self.intervals.len());
+        self.intervals.insert(position, interval);
+        self.logged_secs += interval.secs();
+        Ok(())
+    }
+
+    /// checks an activity read from outside, like a restored backup.
+    /// Activities from before intervals were kept have logged time
+    /// without intervals, so the intervals can add up to less.
+    pub fn validate(&self) -> Result<(), TrackerError> {
+        let invalid = |message: &str| {
+            Err(TrackerError::InvalidValue(format!(
+                "{}: {}",
+                self.name, message
+            )))
+        };
+        if self.name.trim().is_empty() {
+            return invalid("an activity needs a name");
+        }
+        if self.intervals.iter().any(|i| i.start >= i.end) {
+            return invalid("an interval has to end after it starts");
+        }
+        if self
+            .intervals
+            .windows(2)
+            .any(|pair| pair[0].end > pair[1].start)
+        {
+            return invalid("intervals have to be in order without overlaps");
+        }
+        if self.intervals.iter().map(|i| i.secs()).sum::<SecType>() > self.logged_secs {
+            return invalid("the intervals add up to more than the logged time");
+        }
+        if let (Status::ActiveSince(since), Some(last)) = (self.status, self.intervals.last()) {
+            if since < last.end {
+                return invalid("it was started before its last interval ended");
+            }
+        }
+        Ok(())
+    }
+
+    /// closes the running interval at `t` and adds it to the logged time.
+    fn log_until(&mut self, t: SecType) {
+        if let Status::ActiveSince(since) = self.status {
+            self.logged_secs += elapsed_secs(since, t);
+            if t > since {
+                self.intervals.push(Interval {
+                    start: since,
+                    end: t,
+                });
+            }
+        }
+    }
+
+    /// all recorded intervals plus the running one, cut at the
+    /// queries system time.
+    pub fn intervals(&self) -> Vec<Interval> {
+        let mut intervals = self.intervals.clone();
+        if let Status::ActiveSince(since) = self.status {
+            intervals.push(Interval {
+                start: since,
+                end: sys_now_secs(),
+            });
+        }
+        intervals
+    }
+
+    /// logged time without an interval, kept by versions that only
+    /// stored the total.
+    pub fn untimed_secs(&self) -> SecType {
+        let timed: SecType = self.intervals.iter().map(|i| i.secs()).sum();
+        self.logged_secs.saturating_sub(timed)
+    }
+
+    /// the logged secs with a running interval counted up to `now`.
+    pub fn secs_at(&self, now: SecType) -> SecType {
+        match self.status {
+            Status::ActiveSince(since) => self.logged_secs + elapsed_secs(since, now),
+            Status::Idle => self.logged_secs,
+        }
+    }
+
     /// all logged secs plus tha latest active time secs if any.
     pub fn secs_since_creation(&self) -> SecType {
         self.logged_secs + self.status.as_elapsed_secs()
     }
 
     pub fn time_text(&self) -> String {
-        let (hours, mins) = secs_to_hours_minutes(self.secs_since_creation());
-        format!("{:>45}: {}h:{:02}m", self.name, hours, mins)
+        name_time_text(&self.name, self.secs_since_creation())
     }
 
     pub fn is_active(&self) -> bool {
@@ -106,10 +436,38 @@ impl Activity {
     }
 }
 
-pub fn secs_to_hours_minutes(secs: SecType) -> (u8, u8) {
+/// a name and a duration aligned for text tables.
+pub fn name_time_text(name: &str, secs: SecType) -> String {
+    let (hours, mins) = secs_to_hours_minutes(secs);
+    format!("{:>45}: {}h:{:02}m", name, hours, mins)
+}
+
+pub fn secs_to_hours_minutes(secs: SecType) -> (SecType, SecType) {
     let hours = secs / (60 * 60);
     let minutes = (secs % (60 * 60)) / 60;
-    (hours as u8, minutes as u8)
+    (hours, minutes)
+}
+
+/// length of the time covered by any of the intervals.
+/// Overlapping parts are only counted once.
+pub fn wall_clock_secs(intervals: &[Interval]) -> SecType {
+    let mut sorted = intervals.to_vec();
+    sorted.sort_by_key(|i| i.start);
+    let mut total = 0;
+    let mut covered_until = 0;
+    for interval in sorted {
+        let start = interval.start.max(covered_until);
+        if interval.end > start {
+            total += interval.end - start;
+            covered_until = interval.end;
+        }
+    }
+    total
+}
+
+pub fn secs_to_pretty(secs: SecType) -> String {
+    let (hours, mins) = secs_to_hours_minutes(secs);
+    format!("{:02}h:{:02}m", hours, mins)
 }
 
 pub fn sys_now_secs() -> SecType {
@@ -119,8 +477,10 @@ pub fn sys_now_secs() -> SecType {
         .as_secs()
 }
 
+/// A start time after the end time, after a clock change
+/// or an activity scheduled ahead, counts as no time at all.
 pub fn elapsed_secs(t_start: SecType, t_end: SecType) -> SecType {
-    Duration::from_secs(t_end - t_start).as_secs()
+    Duration::from_secs(t_end.saturating_sub(t_start)).as_secs()
 }
 
 pub fn elapsed_since(t_start: SecType) -> SecType {
@@ -128,6 +488,11 @@ pub fn elapsed_since(t_start: SecType) -> SecType {
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::identity_op,
+    clippy::erasing_op,
+    clippy::single_component_path_imports
+)]
 mod test {
     use super::*;
     use serde_json;
@@ -153,14 +518,14 @@ mod test {
         std::thread::sleep(pause);
         assert_eq!(task.secs_since_creation(), pause_secs * 3);
 
-        task.stop();
+        task.stop_at(sys_now_secs());
 
         std::thread::sleep(pause);
         assert_eq!(task.secs_since_creation(), pause_secs * 3);
 
         std::thread::sleep(pause);
         assert_eq!(task.secs_since_creation(), pause_secs * 3);
-        task.start();
+        task.start_at(sys_now_secs());
 
         std::thread::sleep(pause);
         assert_eq!(task.secs_since_creation(), pause_secs * 4);
@@ -231,5 +596,145 @@ mod test {
         assert!(serde_json::to_string(&task)
             .unwrap()
             .contains("all_time_pretty"));
+        assert!(serde_json::to_string(&task).unwrap().contains("intervals"));
+    }
+
+    #[test]
+    fn overlapping_intervals_count_once() {
+        let intervals = vec![
+            Interval { start: 10, end: 20 },
+            Interval { start: 0, end: 15 },
+            Interval { start: 12, end: 14 },
+            Interval { start: 30, end: 40 },
+        ];
+        assert_eq!(wall_clock_secs(&intervals), 30);
+        assert_eq!(wall_clock_secs(&[]), 0);
+    }
+
+    #[test]
+    fn tags_and_notes() {
+        let mut task = Activity::new("task");
+        task.set_tags(&[
+            "a".to_string(),
+            " b ".to_string(),
+            "".to_string(),
+            "a".to_string(),
+        ]);
+        assert_eq!(task.tags(), vec!["a", "b"]);
+        task.set_notes("  ");
+        assert_eq!(task.notes(), None);
+        task.set_notes("fixing the build");
+        assert_eq!(task.notes(), Some("fixing the build".to_string()));
+
+        let changes: ActivityChanges =
+            serde_json::from_str(r#"{"name": "renamed", "project": "", "concurrent": true}"#)
+                .unwrap();
+        task.set_project("billing");
+        task.apply(&changes);
+        assert_eq!(task.name(), "renamed");
+        assert_eq!(task.project(), None);
+        assert_eq!(task.tags(), vec!["a", "b"]);
+        assert!(task.is_concurrent());
+        assert!(serde_json::from_str::<ActivityChanges>(r#"{"colour": "red"}"#).is_err());
+    }
+
+    #[test]
+    fn normalizing_names() {
+        assert_eq!(normalize_name("  code \t review\n").unwrap(), "code review");
+        assert_eq!(normalize_name("a/b?c#d").unwrap(), "a/b?c#d");
+        assert!(normalize_name(" \n ").is_err());
+        assert!(normalize_name("bell\u{7}").is_err());
+        assert!(normalize_name(&"x".repeat(MAX_NAME_CHARS)).is_ok());
+        assert!(normalize_name(&"é".repeat(MAX_NAME_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn resolving_stored_names() {
+        let long = "x".repeat(MAX_NAME_CHARS + 1);
+        let stored = vec![
+            "code  review".to_string(),
+            " mail".to_string(),
+            long.clone(),
+        ];
+        assert_eq!(
+            resolve_name("code  review", &stored).unwrap(),
+            "code  review"
+        );
+        assert_eq!(
+            resolve_name("code review", &stored).unwrap(),
+            "code  review"
+        );
+        assert_eq!(resolve_name("mail", &stored).unwrap(), " mail");
+        assert_eq!(resolve_name(&long, &stored).unwrap(), long);
+        assert_eq!(resolve_name(" new  one", &stored).unwrap(), "new one");
+        assert!(resolve_name(&"y".repeat(MAX_NAME_CHARS + 1), &stored).is_err());
+    }
+
+    #[test]
+    fn adding_intervals() {
+        let mut task = Activity::new("task");
+        task.stop_at(0);
+        task.start_at(100);
+        task.stop_at(200);
+        task.start_at(500);
+        task.stop_at(600);
+        assert!(task
+            .add_interval(Interval {
+                start: 300,
+                end: 300
+            })
+            .is_err());
+        assert!(task
+            .add_interval(Interval {
+                start: 150,
+                end: 250
+            })
+            .is_err());
+        assert!(task
+            .add_interval(Interval {
+                start: 200,
+                end: 300
+            })
+            .is_ok());
+        assert_eq!(
+            task.intervals()[1],
+            Interval {
+                start: 200,
+                end: 300
+            }
+        );
+        assert_eq!(task.secs_since_creation(), 300);
+        task.start_at(sys_now_secs() - 10);
+        let running = Interval {
+            start: sys_now_secs() - 20,
+            end: sys_now_secs() - 5,
+        };
+        assert!(task.add_interval(running).is_err());
+    }
+
+    #[test]
+    fn clipping_intervals() {
+        let interval = Interval { start: 10, end: 20 };
+        assert_eq!(interval.clip(0, 15), Some(Interval { start: 10, end: 15 }));
+        assert_eq!(interval.clip(12, 30), Some(Interval { start: 12, end: 20 }));
+        assert_eq!(interval.clip(20, 30), None);
+        assert_eq!(interval.clip(0, 10), None);
+    }
+
+    #[test]
+    fn stopping_records_intervals() {
+        let mut task = Activity::new("task");
+        let t = sys_now_secs();
+        task.stop_at(t + 10);
+        task.start_at(t + 20);
+        task.stop_at(t + 25);
+        task.stop_at(t + 30);
+        assert_eq!(task.intervals.len(), 2);
+        assert_eq!(
+            task.intervals.iter().map(|i| i.secs()).sum::<SecType>(),
+            task.logged_secs
+        );
+        task.start_at(t + 40);
+        assert_eq!(task.intervals().len(), 3);
     }
 }
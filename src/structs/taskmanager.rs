@@ -1,15 +1,29 @@
+use super::analytics::DayFocus;
+use super::backup::{write_all_atomic, Backup, Settings, BACKUP_VERSION};
+use super::dates::{local_date, local_pretty};
+use super::error::TrackerError;
+use super::filter::TaskFilter;
+use super::history::{History, Switch};
+use super::import::{self, ImportFile, ImportSummary};
+use super::pomodoro::{secs_to_minutes_seconds, Phase, Pomodoro};
+use super::report::Report;
+use super::rounding::Rounding;
+use super::schedule::{Action, Schedule};
+use super::snapshot::{self, Snapshot, SnapshotPolicy};
 use super::task::*;
-use chrono::Local;
+use chrono::{Local, TimeZone, Utc};
 use serde::ser::{SerializeStruct, Serializer};
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::io::prelude::*;
 
 const DAY_LENGTH_SECS: u64 = 7 * 60 * 60 + 30 * 60;
 
 /// The task manager is the only struct one exposed.
 /// It manages a vec of tasks.
-/// Only one task can be active at a time.
+/// Only one task can be active at a time, unless it's concurrent.
 /// Running tasks are exclusive, starting a task will stop all other tasks.
+/// Concurrent tasks, or all tasks in concurrent mode, run next to the others.
 #[derive(Debug, PartialEq, Clone)]
 pub struct TaskManager {
     path: std::path::PathBuf,
@@ -17,6 +31,20 @@ pub struct TaskManager {
     /// pretty system time timestamp for when the taskmanager started
     start_time_pretty: String,
     start_time: SecType,
+    /// billing rules for reported times.
+    rounding: Rounding,
+    /// timebox running on the active activity, if any.
+    pomodoro: Option<Pomodoro>,
+    /// when set, starting a task never stops the others.
+    concurrent: bool,
+    /// the latest switches between activities.
+    history: History,
+    /// periodic copies of the store, none when unset.
+    snapshots: Option<SnapshotPolicy>,
+    /// when the snapshots were last looked at.
+    snapshot_checked: SecType,
+    /// the schedules file couldn't be read on the last tick.
+    schedules_broken: bool,
 }
 
 impl TaskManager {
@@ -26,20 +54,280 @@ impl TaskManager {
             start_time_pretty: format!("{}", Local::now()),
             start_time: sys_now_secs(),
             path,
+            rounding: Rounding::default(),
+            pomodoro: None,
+            concurrent: false,
+            history: History::default(),
+            snapshots: None,
+            snapshot_checked: 0,
+            schedules_broken: false,
         }
     }
 
+    pub fn set_rounding(&mut self, rounding: Rounding) {
+        self.rounding = rounding;
+    }
+
+    pub fn set_concurrent_mode(&mut self, concurrent: bool) {
+        self.concurrent = concurrent;
+    }
+
+    pub fn set_snapshots(&mut self, policy: Option<SnapshotPolicy>) {
+        self.snapshots = policy;
+    }
+
+    /// the tasks the filter lets through, in its order.
+    fn listed_tasks(&self, filter: &TaskFilter) -> Vec<Activity> {
+        filter.apply(self.read())
+    }
+
+    /// the json of the manager with only the tasks the filter lets
+    /// through, the totals count all of them.
+    pub fn listing<'a>(&'a self, filter: &'a TaskFilter) -> Listing<'a> {
+        Listing {
+            manager: self,
+            filter,
+        }
+    }
+
+    /// marks a task as one that runs next to the others.
+    /// The task is created if it doesn't exist yet.
+    pub fn set_concurrent(&mut self, name: &str, concurrent: bool) {
+        self.update_task(name, |t| t.set_concurrent(concurrent));
+    }
+
+    /// puts a task into a project, creating the task if needed.
+    pub fn set_project(&mut self, name: &str, project: &str) {
+        self.update_task(name, |t| t.set_project(project));
+    }
+
+    /// replaces the tags of a task, creating the task if needed.
+    pub fn set_tags(&mut self, name: &str, tags: &[String]) {
+        self.update_task(name, |t| t.set_tags(tags));
+    }
+
+    /// replaces the notes of a task, creating the task if needed.
+    pub fn set_notes(&mut self, name: &str, notes: &str) {
+        self.update_task(name, |t| t.set_notes(notes));
+    }
+
+    /// changes a stored task. A missing task is created stopped,
+    /// so its details can be set before it is first started.
+    fn update_task<F: FnOnce(&mut Activity)>(&mut self, name: &str, update: F) {
+        let mut data = self.read();
+        if !data.iter().any(|x| x.name() == *name) {
+            let mut task = Activity::new(name);
+            task.stop_at(sys_now_secs());
+            data.push(task);
+        }
+        if let Some(task) = data.iter_mut().find(|t| t.name() == name) {
+            update(task);
+        }
+        self.write(data);
+    }
+
+    /// fills in time that was not tracked. Exclusive tasks can't get
+    /// an interval where an other exclusive task was running.
+    /// The task is created stopped if it doesn't exist yet.
+    pub fn add_interval(
+        &mut self,
+        name: &str,
+        start: SecType,
+        end: SecType,
+    ) -> Result<(), TrackerError> {
+        if end > sys_now_secs() {
+            return Err(TrackerError::InvalidTime(format!(
+                "{} is in the future",
+                local_pretty(end)
+            )));
+        }
+        let interval = Interval { start, end };
+        let mut data = self.read();
+        if !data.iter().any(|x| x.name() == *name) {
+            let mut task = Activity::new(name);
+            task.stop_at(sys_now_secs());
+            data.push(task);
+        }
+        let exclusive = !self.concurrent
+            && !data
+                .iter()
+                .any(|task| task.name() == name && task.is_concurrent());
+        if exclusive {
+            let taken = data
+                .iter()
+                .filter(|task| task.name() != name && !task.is_concurrent())
+                .find(|task| task.intervals().iter().any(|i| i.overlaps(&interval)));
+            if let Some(task) = taken {
+                return Err(TrackerError::InvalidTime(format!(
+                    "{} was running in that interval",
+                    task.name()
+                )));
+            }
+        }
+        data.iter_mut()
+            .find(|task| task.name() == name)
+            .unwrap()
+            .add_interval(interval)?;
+        self.write(data);
+        Ok(())
+    }
+
+    /// adds intervals exported from an other tracker. On a dry run
+    /// nothing is saved, the summary tells what would be added.
+    pub fn import(&mut self, file: &ImportFile, dry_run: bool) -> ImportSummary {
+        let mut data = self.read();
+        let mut summary = import::import(&mut data, &file.entries, sys_now_secs());
+        summary.skipped.splice(0..0, file.skipped.iter().cloned());
+        summary.dry_run = dry_run;
+        if !dry_run {
+            self.write(data);
+        }
+        summary
+    }
+
+    fn ids_path(&self) -> std::path::PathBuf {
+        self.path.with_extension("ids.json")
+    }
+
+    /// the highest id given out, so ids of deleted activities are
+    /// not given out again.
+    fn last_id(&self) -> u64 {
+        std::fs::read_to_string(self.ids_path())
+            .ok()
+            .and_then(|text| text.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// the activity with the id.
+    pub fn activity(&self, id: u64) -> Result<Activity, TrackerError> {
+        self.read()
+            .into_iter()
+            .find(|task| task.id() == id)
+            .ok_or_else(|| TrackerError::NotFound(format!("activity {}", id)))
+    }
+
+    /// the stored activity a name given by a user refers to, or the
+    /// normalized name for a new one. See resolve_name.
+    pub fn resolve_name(&self, text: &str) -> Result<String, TrackerError> {
+        let names: Vec<String> = self.read().iter().map(|task| task.name()).collect();
+        resolve_name(text, &names)
+    }
+
+    /// adds a stopped activity, the changes have to name it.
+    pub fn create_activity(&mut self, changes: &ActivityChanges) -> Result<Activity, TrackerError> {
+        let name = normalize_name(changes.name.as_deref().unwrap_or_default())?;
+        let changes = ActivityChanges {
+            name: Some(name.to_owned()),
+            ..changes.clone()
+        };
+        let mut data = self.read();
+        if data.iter().any(|task| same_name(&task.name(), &name)) {
+            return Err(TrackerError::Conflict(format!("{} already exists", name)));
+        }
+        let mut task = Activity::new(&name);
+        task.stop_at(sys_now_secs());
+        task.apply(&changes);
+        data.push(task);
+        self.write(data);
+        Ok(self.read().pop().unwrap())
+    }
+
+    /// changes the details of an activity. A new name can't be the
+    /// name of an other activity.
+    pub fn update_activity(
+        &mut self,
+        id: u64,
+        changes: &ActivityChanges,
+    ) -> Result<Activity, TrackerError> {
+        let mut data = self.read();
+        let old_name = self.activity(id)?.name();
+        let mut changes = changes.clone();
+        if let Some(name) = &changes.name {
+            let name = normalize_name(name)?;
+            if data
+                .iter()
+                .any(|task| task.id() != id && same_name(&task.name(), &name))
+            {
+                return Err(TrackerError::Conflict(format!("{} already exists", name)));
+            }
+            changes.name = Some(name);
+        }
+        // read before anything is written, a broken schedules
+        // file stops the rename.
+        let mut schedules = match &changes.name {
+            Some(name) if *name != old_name => Some(self.schedules()?),
+            _ => None,
+        };
+        let task = data.iter_mut().find(|task| task.id() == id).unwrap();
+        task.apply(&changes);
+        let updated = task.clone();
+        if let Some(pomodoro) = self.pomodoro.as_mut() {
+            if pomodoro.activity == old_name {
+                pomodoro.activity = updated.name();
+            }
+        }
+        self.write(data);
+        if let Some(schedules) = schedules.as_mut() {
+            for schedule in schedules.iter_mut() {
+                schedule.rename(&old_name, &updated.name());
+            }
+            self.write_schedules(schedules);
+            self.history.rename(&old_name, &updated.name());
+        }
+        Ok(updated)
+    }
+
+    /// removes an activity with all its time.
+    pub fn delete_activity(&mut self, id: u64) -> Result<Activity, TrackerError> {
+        let removed = self.activity(id)?;
+        // read before anything is written, a broken schedules
+        // file stops the delete.
+        let mut schedules = self.schedules()?;
+        let mut data = self.read();
+        data.retain(|task| task.id() != id);
+        if self.pomodoro.as_ref().map(|p| p.activity.to_owned()) == Some(removed.name()) {
+            self.pomodoro = None;
+        }
+        self.write(data);
+        schedules.retain_mut(|schedule| schedule.forget(&removed.name()));
+        self.write_schedules(&schedules);
+        self.history.forget(&removed.name());
+        Ok(removed)
+    }
+
+    /// time spent between the two dates, both included.
+    pub fn report(
+        &self,
+        from: chrono::NaiveDate,
+        to: chrono::NaiveDate,
+    ) -> Result<Report, TrackerError> {
+        Report::new(&self.read(), from, to, &self.rounding, DAY_LENGTH_SECS)
+    }
+
+    /// every stored activity, the same data the reports are built on.
+    pub fn activities(&self) -> Vec<Activity> {
+        self.read()
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
     fn read(&self) -> Vec<Activity> {
         let mut file_handle = std::fs::File::open(&self.path).unwrap();
         let mut buf = String::new();
         file_handle.read_to_string(&mut buf).unwrap();
-        let data: Vec<Activity> = serde_json::from_str(buf.as_str()).unwrap();
+        let mut data: Vec<Activity> = serde_json::from_str(buf.as_str()).unwrap();
+        number(&mut data, self.last_id());
         data
     }
 
-    fn write(&self, data: Vec<Activity>) {
+    fn write(&self, mut data: Vec<Activity>) {
+        let last_id = number(&mut data, self.last_id());
+        if last_id > self.last_id() {
+            std::fs::write(self.ids_path(), last_id.to_string()).expect("CAN NOT WRITE IDS.");
+        }
         let data_serialised = serde_json::to_string_pretty(&data).unwrap();
-        dbg!(&data);
         let mut file_handle = std::fs::File::create(&self.path).unwrap();
         file_handle
             .write_all(data_serialised.as_bytes())
@@ -47,27 +335,515 @@ impl TaskManager {
     }
 
     pub fn start(&mut self, name: &str) {
+        self.tick();
+        self.switch_to(name, sys_now_secs());
+    }
+
+    /// starts the activity at an earlier time. The activities it
+    /// stops are cut at the same time.
+    pub fn start_at(&mut self, name: &str, t: SecType) -> Result<(), TrackerError> {
+        self.tick();
+        self.check_backdating(t, None)?;
+        self.switch_to(name, t);
+        Ok(())
+    }
+
+    fn switch_to(&mut self, name: &str, t: SecType) {
+        self.record_switch(Some(name), t);
+        let exclusive = self.activate(name, t);
+        // the timebox ends when its activity is stopped, starting a
+        // concurrent activity next to it leaves it running.
+        let ended = self.pomodoro.as_ref().is_some_and(|pomodoro| {
+            exclusive
+                && pomodoro.activity != name
+                && !self
+                    .read()
+                    .iter()
+                    .any(|task| task.name() == pomodoro.activity && task.is_concurrent())
+        });
+        if ended {
+            self.pomodoro = None;
+        }
+    }
+
+    /// fails like a start at `t` would, without starting anything, so
+    /// a start can be checked before its options are saved.
+    pub fn check_start(&mut self, t: SecType) -> Result<(), TrackerError> {
+        self.tick();
+        self.check_backdating(t, None)
+    }
+
+    /// a backdated start or stop can't go before the last recorded
+    /// change, otherwise intervals would overlap or run backwards.
+    /// With a name only that activity is checked.
+    fn check_backdating(&self, t: SecType, name: Option<&str>) -> Result<(), TrackerError> {
+        if t > sys_now_secs() {
+            return Err(TrackerError::InvalidTime(format!(
+                "{} is in the future",
+                local_pretty(t)
+            )));
+        }
+        let latest = self
+            .read()
+            .iter()
+            .filter(|task| name.map(|name| task.name() == name).unwrap_or(true))
+            .map(|task| task.last_change())
+            .max()
+            .unwrap_or(0);
+        if t < latest {
+            return Err(TrackerError::InvalidTime(format!(
+                "{} is before the last change at {}",
+                local_pretty(t),
+                local_pretty(latest)
+            )));
+        }
+        Ok(())
+    }
+
+    /// starts the activity that was running before the current one.
+    /// Returns the name of the resumed activity, None if there
+    /// is nothing to go back to.
+    pub fn resume(&mut self) -> Option<String> {
+        let running: Vec<String> = self
+            .read()
+            .iter()
+            .filter(|t| t.is_active())
+            .map(|t| t.name())
+            .collect();
+        let previous = self.history.previous(&running)?;
+        self.start(&previous);
+        Some(previous)
+    }
+
+    /// newest first.
+    pub fn history(&self, limit: usize) -> Vec<Switch> {
+        self.history.latest(limit)
+    }
+
+    /// the running activity that is not concurrent, if any.
+    fn foreground_task(&self) -> Option<String> {
+        self.read()
+            .iter()
+            .find(|t| t.is_active() && !t.is_concurrent())
+            .map(|t| t.name())
+    }
+
+    fn record_switch(&mut self, to: Option<&str>, t: SecType) {
+        self.history.push(Switch {
+            at: t,
+            from: self.foreground_task(),
+            to: to.map(String::from),
+        });
+    }
+
+    /// starts the activity with a timebox. With a break the activity
+    /// is stopped and restarted at the end of every phase, without
+    /// one it is only stopped when the work time runs out.
+    /// The pomodoro can be backdated like any other start.
+    pub fn start_pomodoro(
+        &mut self,
+        name: &str,
+        work_secs: SecType,
+        break_secs: SecType,
+        t: SecType,
+    ) -> Result<(), TrackerError> {
+        self.start_at(name, t)?;
+        self.pomodoro = Some(Pomodoro::new(name, work_secs, break_secs, t));
+        self.tick();
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        self.tick();
+        self.stop_all(sys_now_secs());
+    }
+
+    pub fn stop_at(&mut self, t: SecType) -> Result<(), TrackerError> {
+        self.tick();
+        self.check_backdating(t, None)?;
+        self.stop_all(t);
+        Ok(())
+    }
+
+    /// stops a single task, leaving the other running ones alone.
+    pub fn stop_one(&mut self, name: &str, t: SecType) -> Result<(), TrackerError> {
+        if !self.read().iter().any(|task| task.name() == name) {
+            return Err(TrackerError::NotFound(format!("activity {}", name)));
+        }
+        self.tick();
+        self.check_backdating(t, Some(name))?;
+        self.stop_task(name, t);
+        Ok(())
+    }
+
+    fn stop_all(&mut self, t: SecType) {
+        self.pomodoro = None;
+        self.record_switch(None, t);
+        self.deactivate(t);
+    }
+
+    fn stop_task(&mut self, name: &str, t: SecType) {
+        if self.pomodoro.as_ref().map(|p| p.activity == name) == Some(true) {
+            self.pomodoro = None;
+        }
+        if self.foreground_task().as_deref() == Some(name) {
+            self.record_switch(None, t);
+        }
+        let mut data = self.read();
+        data.iter_mut()
+            .filter(|task| task.name() == name)
+            .for_each(|task| task.stop_at(t));
+        self.write(data);
+    }
+
+    /// starts `name`, returns if the other activities were stopped.
+    fn activate(&mut self, name: &str, t: SecType) -> bool {
         let mut data = self.read();
         if !data.iter().any(|x| x.name() == *name) {
             data.push(Activity::new(name));
         }
+        let exclusive = !self.concurrent
+            && !data
+                .iter()
+                .any(|task| task.name() == name && task.is_concurrent());
         for task in data.iter_mut() {
             if task.name() == name {
-                task.start();
-            } else {
-                task.stop();
+                task.start_at(t);
+            } else if exclusive && !task.is_concurrent() {
+                task.stop_at(t);
             }
         }
         self.write(data);
+        exclusive
     }
 
-    pub fn stop(&mut self) {
+    fn deactivate(&mut self, t: SecType) {
         let mut data = self.read();
-        data.iter_mut().for_each(|t| t.stop());
+        data.iter_mut().for_each(|task| task.stop_at(t));
         self.write(data);
     }
 
-    pub fn times(&self) -> String {
+    /// applies everything that should have happened by now.
+    /// Phase changes are applied at the time they were due,
+    /// not at the time they are noticed.
+    pub fn tick(&mut self) {
+        self.tick_at(sys_now_secs());
+    }
+
+    fn tick_at(&mut self, now: SecType) {
+        self.tick_pomodoro(now);
+        self.run_schedules(now);
+        self.tick_snapshots(now);
+    }
+
+    fn tick_pomodoro(&mut self, now: SecType) {
+        while let Some(mut pomodoro) = self.pomodoro.take() {
+            let phase_end = pomodoro.phase_end();
+            if phase_end > now {
+                self.pomodoro = Some(pomodoro);
+                break;
+            }
+            match pomodoro.phase {
+                Phase::Work => {
+                    let mut data = self.read();
+                    for task in data.iter_mut() {
+                        if task.name() == pomodoro.activity {
+                            task.stop_at(phase_end);
+                            task.add_pomodoro();
+                        }
+                    }
+                    self.write(data);
+                }
+                Phase::Break => {
+                    self.activate(&pomodoro.activity, phase_end);
+                }
+            }
+            if pomodoro.has_break() {
+                pomodoro.advance();
+                self.pomodoro = Some(pomodoro);
+            }
+        }
+    }
+
+    /// the store and the settings as one archive.
+    pub fn backup(&self) -> Result<Backup, TrackerError> {
+        Ok(self.archive(self.schedules()?))
+    }
+
+    fn archive(&self, schedules: Vec<Schedule>) -> Backup {
+        Backup {
+            version: BACKUP_VERSION,
+            created: sys_now_secs(),
+            settings: Settings {
+                rounding: self.rounding,
+                concurrent: self.concurrent,
+            },
+            activities: self.read(),
+            schedules,
+        }
+    }
+
+    /// where a restore at `now` keeps the state it replaced, a new
+    /// `<db>.<time>.bak` next to the db file so earlier ones are kept.
+    fn backup_path(&self, now: SecType) -> std::path::PathBuf {
+        let time = Utc
+            .timestamp_opt(now as i64, 0)
+            .unwrap()
+            .format("%Y%m%dT%H%M%SZ");
+        (1..)
+            .map(|count| {
+                let mut path = self.path.as_os_str().to_owned();
+                path.push(format!(".{}", time));
+                if count > 1 {
+                    path.push(format!("-{}", count));
+                }
+                path.push(".bak");
+                std::path::PathBuf::from(path)
+            })
+            .find(|path| !path.exists())
+            .unwrap()
+    }
+
+    /// replaces the store with a checked backup. The replaced state is
+    /// written to a new backup_path first, as a backup that can be
+    /// restored, and its path is returned. An unreadable schedules file
+    /// is left out of it, so a restore can repair it. Settings given on
+    /// the command line win again after a restart.
+    pub fn restore(&mut self, backup: Backup) -> Result<std::path::PathBuf, TrackerError> {
+        backup.validate()?;
+        let backup_path = self.backup_path(sys_now_secs());
+        let previous = self.archive(self.schedules().unwrap_or_default());
+        // ids of the replaced activities are not given out again.
+        let mut activities = backup.activities;
+        let last_id = number(&mut activities, self.last_id());
+        // nothing is replaced unless all files could be written.
+        write_all_atomic(&[
+            (
+                backup_path.to_owned(),
+                serde_json::to_string_pretty(&previous).unwrap(),
+            ),
+            (
+                self.schedules_path(),
+                serde_json::to_string_pretty(&backup.schedules).unwrap(),
+            ),
+            (
+                self.path.to_owned(),
+                serde_json::to_string_pretty(&activities).unwrap(),
+            ),
+            (self.ids_path(), last_id.to_string()),
+        ])
+        .map_err(|error| TrackerError::Storage(error.to_string()))?;
+        self.rounding = backup.settings.rounding;
+        self.concurrent = backup.settings.concurrent;
+        self.pomodoro = None;
+        self.history = History::default();
+        Ok(backup_path)
+    }
+
+    /// the snapshots directory, next to the db file.
+    pub fn snapshots_dir(&self) -> std::path::PathBuf {
+        let mut path = self.path.as_os_str().to_owned();
+        path.push(".snapshots");
+        std::path::PathBuf::from(path)
+    }
+
+    /// the stored snapshots, oldest first.
+    pub fn snapshot_list(&self) -> Vec<Snapshot> {
+        snapshot::list(&self.snapshots_dir())
+    }
+
+    /// restores a snapshot by its file name, like restore does
+    /// with a backup. Returns the snapshot and the path of the backup.
+    pub fn restore_snapshot(
+        &mut self,
+        name: &str,
+    ) -> Result<(Snapshot, std::path::PathBuf), TrackerError> {
+        let found = self
+            .snapshot_list()
+            .into_iter()
+            .find(|snapshot| snapshot.name() == name)
+            .ok_or_else(|| TrackerError::NotFound(format!("snapshot {}", name)))?;
+        let backup = found.read().map_err(TrackerError::InvalidValue)?;
+        let backup_path = self.restore(backup)?;
+        Ok((found, backup_path))
+    }
+
+    /// takes a snapshot when the latest one is older than the policy's
+    /// interval. A store that didn't change since the latest snapshot
+    /// isn't copied again, so the kept snapshots reach further back.
+    fn tick_snapshots(&mut self, now: SecType) {
+        let policy = match self.snapshots {
+            Some(policy) => policy,
+            None => return,
+        };
+        if now < self.snapshot_checked + policy.every.secs() {
+            return;
+        }
+        self.snapshot_checked = now;
+        let current = match self.backup() {
+            Ok(current) => current,
+            Err(error) => {
+                eprintln!("snapshot failed: {}", error);
+                return;
+            }
+        };
+        if let Some(latest) = self.snapshot_list().pop() {
+            if now < latest.created + policy.every.secs() {
+                self.snapshot_checked = latest.created;
+                return;
+            }
+            if let Ok(stored) = latest.read() {
+                if stored.activities == current.activities && stored.schedules == current.schedules
+                {
+                    return;
+                }
+            }
+        }
+        if let Err(error) = snapshot::take(&self.snapshots_dir(), &current, now, policy.keep) {
+            eprintln!("snapshot failed: {}", error);
+        }
+    }
+
+    fn schedules_path(&self) -> std::path::PathBuf {
+        self.path.with_extension("schedules.json")
+    }
+
+    /// the stored schedules, none without a schedules file. A file that
+    /// can't be read or holds invalid schedules is a storage error.
+    pub fn schedules(&self) -> Result<Vec<Schedule>, TrackerError> {
+        let path = self.schedules_path();
+        let buf = match std::fs::read_to_string(&path) {
+            Ok(buf) => buf,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(TrackerError::Storage(error.to_string())),
+        };
+        let broken =
+            |error: String| TrackerError::Storage(format!("{}: {}", path.display(), error));
+        let schedules: Vec<Schedule> =
+            serde_json::from_str(&buf).map_err(|error| broken(error.to_string()))?;
+        for schedule in &schedules {
+            schedule
+                .validate()
+                .map_err(|error| broken(format!("schedule {}: {}", schedule.id, error)))?;
+        }
+        Ok(schedules)
+    }
+
+    fn write_schedules(&self, schedules: &[Schedule]) {
+        let data_serialised = serde_json::to_string_pretty(schedules).unwrap();
+        std::fs::write(self.schedules_path(), data_serialised).expect("CAN NOT WRITE SCHEDULES.");
+    }
+
+    pub fn add_schedule(
+        &mut self,
+        at: &str,
+        action: Action,
+        daily: bool,
+    ) -> Result<Schedule, TrackerError> {
+        let mut schedules = self.schedules()?;
+        let id = schedules.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        // a start names an activity like a start from the api does.
+        let action = match action {
+            Action::Start { name, minutes } => Action::Start {
+                name: self.resolve_name(&name)?,
+                minutes,
+            },
+            Action::Stop => Action::Stop,
+        };
+        let schedule = Schedule::new(id, at, action, daily, sys_now_secs())?;
+        schedules.push(schedule.clone());
+        self.write_schedules(&schedules);
+        Ok(schedule)
+    }
+
+    pub fn remove_schedule(&mut self, id: u64) -> Result<(), TrackerError> {
+        let mut schedules = self.schedules()?;
+        let count = schedules.len();
+        schedules.retain(|s| s.id != id);
+        if schedules.len() == count {
+            return Err(TrackerError::NotFound(format!("schedule {}", id)));
+        }
+        self.write_schedules(&schedules);
+        Ok(())
+    }
+
+    /// runs what is due. A broken schedules file is reported once and
+    /// left alone until it can be read again.
+    fn run_schedules(&mut self, now: SecType) {
+        let mut schedules = match self.schedules() {
+            Ok(schedules) => schedules,
+            Err(error) => {
+                if !self.schedules_broken {
+                    eprintln!("schedules not run: {}", error);
+                }
+                self.schedules_broken = true;
+                return;
+            }
+        };
+        self.schedules_broken = false;
+        let mut changed = false;
+        for schedule in schedules.iter_mut() {
+            changed |= self.end_timed_start(schedule, now);
+            if let Some(due) = schedule.due(now) {
+                self.run_action(schedule, due, now);
+                changed = true;
+            }
+            changed |= self.end_timed_start(schedule, now);
+        }
+        if changed {
+            schedules.retain(|s| !s.is_finished());
+            self.write_schedules(&schedules);
+        }
+    }
+
+    /// the time a scheduled action is applied at. If backdating to the
+    /// due time would break the recorded intervals it happens now.
+    fn scheduled_time(&self, due: SecType, now: SecType) -> SecType {
+        match self.check_backdating(due, None) {
+            Ok(()) => due,
+            Err(_) => now,
+        }
+    }
+
+    fn run_action(&mut self, schedule: &mut Schedule, due: SecType, now: SecType) {
+        let t = self.scheduled_time(due, now);
+        match &schedule.action {
+            Action::Stop => self.stop_all(t),
+            Action::Start { name, minutes } => {
+                let previous = self.foreground_task();
+                self.switch_to(name, t);
+                if let Some(minutes) = minutes {
+                    schedule.return_at = Some(t + minutes * 60);
+                    schedule.return_to = previous.filter(|previous| previous != name);
+                }
+            }
+        }
+        schedule.last_run = Some(due);
+    }
+
+    /// goes back to the previous activity when a timed start is over.
+    /// If the scheduled activity isn't running anymore nothing changes.
+    fn end_timed_start(&mut self, schedule: &mut Schedule, now: SecType) -> bool {
+        let return_at = match schedule.return_at {
+            Some(return_at) if return_at <= now => return_at,
+            _ => return false,
+        };
+        if let Action::Start { name, .. } = &schedule.action {
+            if self.foreground_task().as_deref() == Some(name) {
+                let t = self.scheduled_time(return_at, now);
+                match schedule.return_to.take() {
+                    Some(previous) => self.switch_to(&previous, t),
+                    None => self.stop_task(name, t),
+                }
+            }
+        }
+        schedule.return_at = None;
+        schedule.return_to = None;
+        true
+    }
+
+    /// the pretty text, listing the tasks the filter lets through.
+    pub fn times(&self, filter: &TaskFilter) -> String {
         let mut result = format!("start time:         {}", self.start_time_pretty.to_owned());
         let (hh, mm) = &secs_to_hours_minutes(elapsed_since(self.start_time));
         result.push_str(&format!("\nelapsed day:        {:02}h:{:02}m", hh, mm));
@@ -78,17 +854,57 @@ impl TaskManager {
             "\ntotal acivity time: {:02}h:{:02}m",
             hours, minutes
         ));
+        let overlapping_time = total_activity_time.saturating_sub(self.wall_clock_time());
+        if overlapping_time > 0 {
+            result.push_str(&format!(
+                "\nwall clock time:    {}",
+                secs_to_pretty(self.wall_clock_time())
+            ));
+            result.push_str(&format!(
+                "\noverlapping time:   {}",
+                secs_to_pretty(overlapping_time)
+            ));
+        }
+        if let Some(pomodoro) = &self.pomodoro {
+            result.push_str(&format!(
+                "\npomodoro:           {:?} on {}, {} left",
+                pomodoro.phase,
+                pomodoro.activity,
+                secs_to_minutes_seconds(pomodoro.remaining_secs(sys_now_secs()))
+            ));
+        }
+        if self.rounding.is_enabled() {
+            result.push_str(&format!(
+                "\ntotal billable time: {}",
+                secs_to_pretty(self.total_billable_time())
+            ));
+        }
+        if let Ok(focus) = DayFocus::new(&self.read(), local_date(sys_now_secs())) {
+            if focus.tracked_secs > 0 {
+                result.push_str(&format!("\nfocus today:        {}", focus.text()));
+            }
+        }
         result.push('\n');
         result.push_str(
             &self
-                .read()
+                .listed_tasks(filter)
                 .iter()
                 .map(|t| {
-                    if t.is_active() {
+                    let mut line = if t.is_active() {
                         format!("> {}", t.time_text())
                     } else {
                         format!("  {}", t.time_text())
+                    };
+                    if t.is_concurrent() {
+                        line.push_str(" [concurrent]");
+                    }
+                    if self.rounding.is_enabled() {
+                        line.push_str(&format!(
+                            " (billable {})",
+                            secs_to_pretty(self.rounding.billable_activity(t))
+                        ));
                     }
+                    line
                 })
                 .collect::<Vec<String>>()
                 .join("\n"),
@@ -98,8 +914,60 @@ impl TaskManager {
     }
 
     fn total_activity_time(&self) -> SecType {
-        self.read().iter().map(|t| t.secs_since_creation()).sum()
+        let now = sys_now_secs();
+        self.read().iter().map(|t| t.secs_at(now)).sum()
     }
+
+    /// time when at least one task was running. With concurrent
+    /// tasks this is less than the total activity time.
+    fn wall_clock_time(&self) -> SecType {
+        let intervals: Vec<Interval> = self.read().iter().flat_map(|t| t.intervals()).collect();
+        wall_clock_secs(&intervals)
+    }
+
+    /// rounded time per activity. The raw stored times are not touched.
+    fn billable_times(&self) -> BTreeMap<String, SecType> {
+        self.read()
+            .iter()
+            .map(|t| (t.name(), self.rounding.billable_activity(t)))
+            .collect()
+    }
+
+    fn total_billable_time(&self) -> SecType {
+        self.billable_times().values().sum()
+    }
+}
+
+/// The running pomodoro as shown in the json.
+#[derive(Serialize)]
+struct PomodoroState<'a> {
+    activity: &'a str,
+    phase: Phase,
+    remaining_secs: SecType,
+    remaining_pretty: String,
+    completed: u32,
+}
+
+/// gives the activities without an id the next ones after `last_id`
+/// and the stored ids, in order. Returns the highest id.
+fn number(data: &mut [Activity], last_id: u64) -> u64 {
+    let mut last = data
+        .iter()
+        .map(|task| task.id())
+        .max()
+        .unwrap_or(0)
+        .max(last_id);
+    for task in data.iter_mut().filter(|task| task.id() == 0) {
+        last += 1;
+        task.set_id(last);
+    }
+    last
+}
+
+/// The manager as json with the tasks narrowed down by a filter.
+pub struct Listing<'a> {
+    manager: &'a TaskManager,
+    filter: &'a TaskFilter,
 }
 
 impl Serialize for TaskManager {
@@ -107,38 +975,92 @@ impl Serialize for TaskManager {
     where
         S: Serializer,
     {
-        let (hours, mins) = secs_to_hours_minutes(self.total_activity_time());
+        self.listing(&TaskFilter::default()).serialize(serializer)
+    }
+}
+
+impl Serialize for Listing<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (manager, filter) = (self.manager, self.filter);
+        let (hours, mins) = secs_to_hours_minutes(manager.total_activity_time());
         let total_time = format!("{:02}h:{:02}m", hours, mins);
         let mut state = serializer.serialize_struct("Taskmanager", 3)?;
-        let (hh, mm) = &secs_to_hours_minutes(elapsed_since(self.start_time));
+        let (hh, mm) = &secs_to_hours_minutes(elapsed_since(manager.start_time));
         let elapsed_day = &format!("{:02}h:{:02}m", hh, mm);
-        let time_diff = elapsed_since(self.start_time).saturating_sub(self.total_activity_time());
+        let time_diff = elapsed_since(manager.start_time).saturating_sub(manager.wall_clock_time());
         let (tdelta_hh, tdelta_mm) = secs_to_hours_minutes(time_diff);
         let time_diff_pretty = &format!("{:02}h:{:02}m", tdelta_hh, tdelta_mm);
 
-        state.serialize_field("tasks", &self.read())?;
-        state.serialize_field("start_time_pretty", &self.start_time_pretty)?;
+        state.serialize_field("tasks", &manager.listed_tasks(filter))?;
+        state.serialize_field("start_time_pretty", &manager.start_time_pretty)?;
         state.serialize_field("elapsed_day", &elapsed_day)?;
         state.serialize_field("total_activity_time", &total_time)?;
         state.serialize_field("time_difference", &time_diff_pretty)?;
-        state.serialize_field("start_time:", &self.start_time)?;
-        state.serialize_field("display:", &self.times())?;
+        let wall_clock_time = manager.wall_clock_time();
+        state.serialize_field("wall_clock_time", &secs_to_pretty(wall_clock_time))?;
+        state.serialize_field(
+            "overlapping_time",
+            &secs_to_pretty(
+                manager
+                    .total_activity_time()
+                    .saturating_sub(wall_clock_time),
+            ),
+        )?;
+        state.serialize_field("start_time:", &manager.start_time)?;
+        state.serialize_field("display:", &manager.times(filter))?;
 
         let (day_len_hh, day_len_mm) = secs_to_hours_minutes(DAY_LENGTH_SECS);
         let day_length = &format!("{:02}h:{:02}m", day_len_hh, day_len_mm);
         state.serialize_field("day_length", day_length)?;
 
         let (time_left_hh, time_left_mm) =
-            secs_to_hours_minutes(DAY_LENGTH_SECS - self.total_activity_time());
+            secs_to_hours_minutes(DAY_LENGTH_SECS.saturating_sub(manager.wall_clock_time()));
         let time_left = &format!("{:02}h:{:02}m", time_left_hh, time_left_mm);
         state.serialize_field("time_left", time_left)?;
+
+        let billable: BTreeMap<String, String> = manager
+            .billable_times()
+            .into_iter()
+            .map(|(name, secs)| (name, secs_to_pretty(secs)))
+            .collect();
+        state.serialize_field("billable", &billable)?;
+        state.serialize_field(
+            "total_billable_time",
+            &secs_to_pretty(manager.total_billable_time()),
+        )?;
+        state.serialize_field("rounding", &manager.rounding)?;
+
+        let pomodoro = manager.pomodoro.as_ref().map(|pomodoro| {
+            let remaining_secs = pomodoro.remaining_secs(sys_now_secs());
+            let completed = manager
+                .read()
+                .iter()
+                .find(|t| t.name() == pomodoro.activity)
+                .map(|t| t.pomodoros())
+                .unwrap_or(0);
+            PomodoroState {
+                activity: &pomodoro.activity,
+                phase: pomodoro.phase,
+                remaining_secs,
+                remaining_pretty: secs_to_minutes_seconds(remaining_secs),
+                completed,
+            }
+        });
+        state.serialize_field("pomodoro", &pomodoro)?;
         state.end()
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod test {
     use super::*;
+    use crate::structs::import::ImportEntry;
+    use crate::structs::rounding::{RoundingMode, RoundingScope};
+    use crate::structs::snapshot::Every;
 
     #[test]
     fn creating_task_manager() {
@@ -262,5 +1184,362 @@ mod test {
         assert!(tm_json.contains(&"time_difference"));
         assert!(tm_json.contains(&"start_time"));
         assert!(tm_json.contains(&"display"));
+        assert!(tm_json.contains("billable"));
+        assert!(tm_json.contains("total_billable_time"));
+    }
+
+    /// An empty db in a directory of its own. The directory goes away
+    /// with everything the task manager put next to the db when the
+    /// guard is dropped.
+    struct TestDir(std::path::PathBuf);
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn test_manager(name: &str, db: &str) -> (TaskManager, TestDir) {
+        let dir = std::env::temp_dir().join(format!("timetracker-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("db.json");
+        std::fs::write(&path, db).unwrap();
+        (TaskManager::new(path), TestDir(dir))
+    }
+
+    #[test]
+    fn rounding_leaves_stored_time_alone() {
+        let (mut tm, _dir) = test_manager("rounding_leaves_stored_time_alone", "[]");
+        tm.set_rounding(Rounding {
+            increment_secs: 15 * 60,
+            mode: RoundingMode::Up,
+            scope: RoundingScope::Interval,
+            minimum_secs: 0,
+        });
+        tm.start("task");
+        pause();
+        tm.stop();
+        assert_eq!(tm.read()[0].secs_since_creation(), 1);
+        assert_eq!(tm.billable_times()["task"], 15 * 60);
+        assert!(tm
+            .times(&TaskFilter::default())
+            .contains("billable 00h:15m"));
+    }
+
+    #[test]
+    fn pomodoro_cycles() {
+        let (mut tm, _dir) = test_manager("pomodoro_cycles", "[]");
+        tm.start_pomodoro("focus", 25 * 60, 5 * 60, sys_now_secs())
+            .unwrap();
+        let started = tm.pomodoro.as_ref().unwrap().phase_started;
+        tm.tick_at(started + 10 * 60);
+        assert!(tm.read()[0].is_active());
+        tm.tick_at(started + 26 * 60);
+        assert!(!tm.read()[0].is_active());
+        assert_eq!(tm.read()[0].pomodoros(), 1);
+        assert_eq!(tm.read()[0].secs_since_creation(), 25 * 60);
+        assert_eq!(tm.pomodoro.as_ref().unwrap().phase, Phase::Break);
+        tm.tick_at(started + 31 * 60);
+        assert!(tm.read()[0].is_active());
+        assert_eq!(tm.pomodoro.as_ref().unwrap().phase, Phase::Work);
+        assert_eq!(
+            tm.pomodoro.as_ref().unwrap().phase_started,
+            started + 30 * 60
+        );
+    }
+
+    #[test]
+    fn concurrent_start_keeps_the_pomodoro() {
+        let (mut tm, _dir) = test_manager("concurrent_start_keeps_the_pomodoro", "[]");
+        tm.set_concurrent("on-call", true);
+        tm.start_pomodoro("focus", 25 * 60, 5 * 60, sys_now_secs())
+            .unwrap();
+        tm.start("on-call");
+        assert_eq!(tm.pomodoro.as_ref().unwrap().activity, "focus");
+        tm.start("mail");
+        assert!(tm.pomodoro.is_none());
+    }
+
+    #[test]
+    fn concurrent_tasks_keep_running() {
+        let (mut tm, _dir) = test_manager("concurrent_tasks_keep_running", "[]");
+        tm.set_concurrent("on-call", true);
+        tm.start("on-call");
+        tm.start("alpha");
+        tm.start("beta");
+        let active = |tm: &TaskManager| {
+            tm.read()
+                .iter()
+                .filter(|t| t.is_active())
+                .map(|t| t.name())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(active(&tm), vec!["on-call", "beta"]);
+        pause();
+        assert_eq!(tm.total_activity_time(), 2);
+        assert_eq!(tm.wall_clock_time(), 1);
+        tm.stop_one("on-call", sys_now_secs()).unwrap();
+        assert_eq!(active(&tm), vec!["beta"]);
+        tm.set_concurrent_mode(true);
+        tm.start("alpha");
+        assert_eq!(active(&tm), vec!["alpha", "beta"]);
+        tm.stop();
+        assert!(active(&tm).is_empty());
+    }
+
+    #[test]
+    fn resume_previous_task() {
+        let (mut tm, _dir) = test_manager("resume_previous_task", "[]");
+        assert_eq!(tm.resume(), None);
+        tm.start("work");
+        tm.start("call");
+        assert_eq!(tm.resume(), Some("work".to_string()));
+        assert_eq!(tm.foreground_task(), Some("work".to_string()));
+        assert_eq!(tm.resume(), Some("call".to_string()));
+        tm.stop();
+        assert_eq!(tm.resume(), Some("call".to_string()));
+        let history = tm.history(2);
+        assert_eq!(history[0].to, Some("call".to_string()));
+        assert_eq!(history[1].to, None);
+    }
+
+    #[test]
+    fn backdated_start_cuts_previous_task() {
+        let (mut tm, _dir) = test_manager("backdated_start_cuts_previous_task", "[]");
+        let now = sys_now_secs();
+        tm.start_at("alpha", now - 60 * 60).unwrap();
+        tm.start_at("beta", now - 15 * 60).unwrap();
+        assert_eq!(tm.read()[0].secs_since_creation(), 45 * 60);
+        assert_eq!(tm.read()[1].secs_at(now), 15 * 60);
+        assert!(tm.start_at("alpha", now - 30 * 60).is_err());
+        assert!(tm.check_start(now - 30 * 60).is_err());
+        assert!(tm.check_start(now - 10 * 60).is_ok());
+        assert!(tm.stop_at(now + 60).is_err());
+        tm.stop_at(now - 5 * 60).unwrap();
+        assert_eq!(tm.read()[1].secs_since_creation(), 10 * 60);
+        assert!(tm.stop_one("beta", now - 10 * 60).is_err());
+        assert!(matches!(
+            tm.stop_one("gamma", now),
+            Err(TrackerError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn scheduled_start_resumes_previous_task() {
+        let (mut tm, _dir) = test_manager("scheduled_start_resumes_previous_task", "[]");
+        let now = sys_now_secs();
+        tm.start_at("work", now - 60 * 60).unwrap();
+        let standup = Action::Start {
+            name: "standup".to_string(),
+            minutes: Some(15),
+        };
+        let mut schedule = tm.add_schedule("09:30", standup, false).unwrap();
+        tm.run_action(&mut schedule, now - 30 * 60, now);
+        assert_eq!(tm.foreground_task(), Some("standup".to_string()));
+        assert_eq!(schedule.return_at, Some(now - 15 * 60));
+        assert!(tm.end_timed_start(&mut schedule, now));
+        assert_eq!(tm.foreground_task(), Some("work".to_string()));
+        assert_eq!(tm.read()[1].secs_since_creation(), 15 * 60);
+        assert!(schedule.is_finished());
+        tm.remove_schedule(schedule.id).unwrap();
+        assert!(tm.remove_schedule(schedule.id).is_err());
+        assert!(tm.schedules().unwrap().is_empty());
+
+        std::fs::write(tm.schedules_path(), "[{").unwrap();
+        tm.tick_at(now);
+        assert!(matches!(tm.schedules(), Err(TrackerError::Storage(_))));
+        assert!(tm.add_schedule("09:30", Action::Stop, true).is_err());
+    }
+
+    #[test]
+    fn adding_intervals_to_gaps() {
+        let (mut tm, _dir) = test_manager("adding_intervals_to_gaps", "[]");
+        let now = sys_now_secs();
+        tm.start_at("first", now - 3600).unwrap();
+        tm.stop_at(now - 3000).unwrap();
+        tm.start_at("second", now - 1000).unwrap();
+        assert!(tm.add_interval("gap", now - 3100, now - 2000).is_err());
+        assert!(tm.add_interval("gap", now - 3000, now - 1000).is_ok());
+        assert!(tm.add_interval("gap", now - 900, now + 100).is_err());
+        assert!(matches!(
+            tm.add_interval("gap", now - 900, 9_999_999_999_999),
+            Err(TrackerError::InvalidTime(_))
+        ));
+        assert!(tm.add_interval("gap", now - 1500, now - 1200).is_err());
+        tm.set_concurrent("meeting", true);
+        assert!(tm.add_interval("meeting", now - 3600, now - 1000).is_ok());
+        let gap = tm.read().into_iter().find(|t| t.name() == "gap").unwrap();
+        assert_eq!(gap.secs_since_creation(), 2000);
+        assert!(!gap.is_active());
+    }
+
+    #[test]
+    fn dry_run_import_saves_nothing() {
+        let (mut tm, _dir) = test_manager("dry_run_import_saves_nothing", "[]");
+        let now = sys_now_secs();
+        let file = ImportFile::from(vec![ImportEntry {
+            name: "imported".to_string(),
+            project: Some("old tracker".to_string()),
+            tags: Vec::new(),
+            notes: None,
+            start: now - 7200,
+            end: now - 3600,
+            precision: 1,
+        }]);
+        let summary = tm.import(&file, true);
+        assert!(summary.dry_run);
+        assert_eq!(summary.intervals, 1);
+        assert!(tm.read().is_empty());
+        tm.import(&file, false);
+        assert_eq!(tm.read()[0].secs_since_creation(), 3600);
+        assert_eq!(tm.import(&file, false).duplicates.len(), 1);
+    }
+
+    #[test]
+    fn restoring_a_backup() {
+        let (mut tm, _dir) = test_manager("restoring_a_backup", "[]");
+        let now = sys_now_secs();
+        tm.start_at("first", now - 3600).unwrap();
+        tm.stop_at(now - 1800).unwrap();
+        tm.add_schedule("09:00", Action::Stop, true).unwrap();
+        let mut backup = tm.backup().unwrap();
+        backup.settings.concurrent = true;
+
+        tm.start("second");
+        let mut broken = backup.clone();
+        broken.version = 0;
+        assert!(tm.restore(broken).is_err());
+        assert_eq!(tm.read().len(), 2);
+
+        let first = tm.restore(backup.clone()).unwrap();
+        assert_eq!(tm.read(), backup.activities);
+        assert_eq!(tm.schedules().unwrap(), backup.schedules);
+        assert!(tm.concurrent);
+        let previous: Backup =
+            serde_json::from_str(&std::fs::read_to_string(&first).unwrap()).unwrap();
+        assert_eq!(previous.activities.len(), 2);
+
+        // a second restore keeps the first backup.
+        let second = tm.restore(backup.clone()).unwrap();
+        assert_ne!(first, second);
+        assert!(first.exists());
+        assert!(second.to_string_lossy().ends_with(".bak"));
+
+        // restored ids are not given out again after a delete.
+        let (mut fresh, _fresh_dir) = test_manager("restoring_a_backup_ids", "[]");
+        fresh.restore(backup.clone()).unwrap();
+        fresh.delete_activity(1).unwrap();
+        fresh.start("third");
+        assert_eq!(fresh.read()[0].id(), 2);
+    }
+
+    #[test]
+    fn snapshots_on_tick() {
+        let (mut tm, _dir) = test_manager("snapshots_on_tick", "[]");
+        let now = sys_now_secs();
+        tm.start_at("first", now - 7200).unwrap();
+        tm.stop_at(now - 7000).unwrap();
+        tm.set_snapshots(Some(SnapshotPolicy {
+            every: Every::Hour,
+            keep: 2,
+        }));
+        tm.tick_snapshots(now);
+        tm.tick_snapshots(now + 1800);
+        assert_eq!(tm.snapshot_list().len(), 1);
+        // nothing changed, an hour later there is still one.
+        tm.tick_snapshots(now + 3600);
+        assert_eq!(tm.snapshot_list().len(), 1);
+        let first = tm.snapshot_list()[0].name();
+
+        tm.start_at("second", now - 600).unwrap();
+        tm.tick_snapshots(now + 7200);
+        tm.stop_at(now - 300).unwrap();
+        tm.tick_snapshots(now + 3 * 3600);
+        assert_eq!(tm.snapshot_list().len(), 2);
+        assert!(tm.snapshot_list().iter().all(|s| s.name() != first));
+
+        tm.restore_snapshot(&tm.snapshot_list()[0].name()).unwrap();
+        assert!(tm.read()[1].is_active());
+        assert!(tm.restore_snapshot(&first).is_err());
+    }
+
+    #[test]
+    fn activities_by_id() {
+        let (mut tm, _dir) = test_manager(
+            "activities_by_id",
+            r#"[{"added_at": "", "status": "Idle", "logged_secs": 60, "name": "old"}]"#,
+        );
+        assert_eq!(tm.read()[0].id(), 1);
+        let changes = |json: &str| serde_json::from_str::<ActivityChanges>(json).unwrap();
+
+        let created = tm
+            .create_activity(&changes(r#"{"name": "review", "project": "billing"}"#))
+            .unwrap();
+        assert_eq!(created.id(), 2);
+        assert!(!created.is_active());
+        assert_eq!(
+            tm.activity(2).unwrap().project(),
+            Some("billing".to_string())
+        );
+        assert!(matches!(
+            tm.create_activity(&changes(r#"{"name": "review"}"#)),
+            Err(TrackerError::Conflict(_))
+        ));
+        assert!(tm.create_activity(&changes(r#"{"notes": "x"}"#)).is_err());
+
+        assert!(matches!(
+            tm.update_activity(2, &changes(r#"{"name": "old"}"#)),
+            Err(TrackerError::Conflict(_))
+        ));
+        tm.add_schedule(
+            "09:00",
+            Action::Start {
+                name: "review".to_string(),
+                minutes: None,
+            },
+            true,
+        )
+        .unwrap();
+        tm.start("review");
+        tm.start("mail");
+        let renamed = tm
+            .update_activity(2, &changes(r#"{"name": "code review"}"#))
+            .unwrap();
+        assert_eq!(renamed.project(), Some("billing".to_string()));
+        assert_eq!(tm.activity(2).unwrap().name(), "code review");
+        assert_eq!(
+            tm.schedules().unwrap()[0].action,
+            Action::Start {
+                name: "code review".to_string(),
+                minutes: None,
+            }
+        );
+        assert_eq!(tm.history(1)[0].from, Some("code review".to_string()));
+
+        tm.delete_activity(2).unwrap();
+        assert!(matches!(tm.activity(2), Err(TrackerError::NotFound(_))));
+        assert!(tm.schedules().unwrap().is_empty());
+        assert!(tm
+            .history(usize::MAX)
+            .iter()
+            .all(|switch| switch.from.as_deref() != Some("code review")
+                && switch.to.as_deref() != Some("code review")));
+        assert_eq!(tm.activity(3).unwrap().name(), "mail");
+        let created = tm
+            .create_activity(&changes(r#"{"name": "planning"}"#))
+            .unwrap();
+        assert_eq!(created.id(), 4);
+    }
+
+    #[test]
+    fn timebox_without_break_stops() {
+        let (mut tm, _dir) = test_manager("timebox_without_break_stops", "[]");
+        tm.start_pomodoro("focus", 60, 0, sys_now_secs()).unwrap();
+        let started = tm.pomodoro.as_ref().unwrap().phase_started;
+        tm.tick_at(started + 10 * 60);
+        assert!(!tm.read()[0].is_active());
+        assert_eq!(tm.read()[0].pomodoros(), 1);
+        assert_eq!(tm.pomodoro, None);
     }
 }
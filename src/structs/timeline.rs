@@ -0,0 +1,162 @@
+use super::dates::{day_start, next_day};
+use super::error::TrackerError;
+use super::task::*;
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::Serialize;
+
+/// One block of the timeline, an interval of an activity or a gap.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Block {
+    /// the activity, None for gaps.
+    pub name: Option<String>,
+    pub project: Option<String>,
+    pub start: SecType,
+    pub end: SecType,
+    /// local HH:MM.
+    pub start_pretty: String,
+    pub end_pretty: String,
+    pub secs: SecType,
+}
+
+impl Block {
+    fn new(task: Option<&Activity>, interval: Interval) -> Self {
+        Self {
+            name: task.map(|t| t.name()),
+            project: task.and_then(|t| t.project()),
+            start: interval.start,
+            end: interval.end,
+            start_pretty: time_of_day(interval.start),
+            end_pretty: time_of_day(interval.end),
+            secs: interval.secs(),
+        }
+    }
+}
+
+/// The intervals of a day in the order they started and the
+/// untracked gaps between the first start and the last stop.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Timeline {
+    pub date: String,
+    /// local midnight starting and ending the day.
+    pub day_start: SecType,
+    pub day_end: SecType,
+    pub intervals: Vec<Block>,
+    pub gaps: Vec<Block>,
+    pub tracked_secs: SecType,
+    pub gap_secs: SecType,
+}
+
+impl Timeline {
+    /// gaps shorter than `min_gap` secs are left out.
+    pub fn new(
+        tasks: &[Activity],
+        date: NaiveDate,
+        min_gap: SecType,
+    ) -> Result<Self, TrackerError> {
+        let start = day_start(date);
+        let end = day_start(next_day(date)?);
+        let mut intervals: Vec<Block> = tasks
+            .iter()
+            .flat_map(|task| {
+                task.intervals_between(start, end)
+                    .into_iter()
+                    .map(move |interval| Block::new(Some(task), interval))
+            })
+            .collect();
+        intervals.sort_by(|a, b| a.start.cmp(&b.start).then(a.name.cmp(&b.name)));
+
+        let mut gaps = Vec::new();
+        let mut covered_until: Option<SecType> = None;
+        for block in intervals.iter() {
+            if let Some(until) = covered_until {
+                if block.start >= until.saturating_add(min_gap.max(1)) {
+                    gaps.push(Block::new(
+                        None,
+                        Interval {
+                            start: until,
+                            end: block.start,
+                        },
+                    ));
+                }
+            }
+            covered_until = Some(covered_until.unwrap_or(0).max(block.end));
+        }
+
+        let all: Vec<Interval> = intervals
+            .iter()
+            .map(|block| Interval {
+                start: block.start,
+                end: block.end,
+            })
+            .collect();
+        Ok(Self {
+            date: date.format("%Y-%m-%d").to_string(),
+            day_start: start,
+            day_end: end,
+            tracked_secs: wall_clock_secs(&all),
+            gap_secs: gaps.iter().map(|gap| gap.secs).sum(),
+            intervals,
+            gaps,
+        })
+    }
+}
+
+fn time_of_day(secs: SecType) -> String {
+    Local
+        .timestamp_opt(secs as i64, 0)
+        .unwrap()
+        .format("%H:%M")
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structs::dates::parse_date;
+
+    #[test]
+    fn intervals_and_gaps() {
+        let date = parse_date("2026-10-14").unwrap();
+        let nine = day_start(date) + 9 * 60 * 60;
+        let mut alpha = Activity::new("alpha");
+        alpha.stop_at(0);
+        alpha.start_at(nine);
+        alpha.stop_at(nine + 3600);
+        alpha.start_at(nine + 7200);
+        alpha.stop_at(nine + 7230);
+        let mut beta = Activity::new("beta");
+        beta.set_concurrent(true);
+        beta.stop_at(0);
+        beta.start_at(nine + 1800);
+        beta.stop_at(nine + 5400);
+        beta.start_at(nine + 7260);
+        beta.stop_at(nine + 7300);
+
+        let timeline = Timeline::new(&[beta, alpha], date, 60).unwrap();
+        let names: Vec<Option<String>> = timeline
+            .intervals
+            .iter()
+            .map(|b| b.name.to_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [Some("alpha"), Some("beta"), Some("alpha"), Some("beta")].map(|n| n.map(String::from))
+        );
+        assert_eq!(timeline.gaps.len(), 1);
+        assert_eq!(timeline.gaps[0].start, nine + 5400);
+        assert_eq!(timeline.gaps[0].end, nine + 7200);
+        assert_eq!(timeline.gap_secs, 1800);
+        assert_eq!(timeline.gaps[0].start_pretty, "10:30");
+        assert_eq!(timeline.tracked_secs, 5400 + 30 + 40);
+
+        let timeline = Timeline::new(&[], date, 60).unwrap();
+        assert!(timeline.intervals.is_empty());
+        assert!(timeline.gaps.is_empty());
+    }
+
+    #[test]
+    fn last_day_is_an_error() {
+        let result = Timeline::new(&[], NaiveDate::MAX, 60);
+        assert!(matches!(result, Err(TrackerError::InvalidTime(_))));
+    }
+}